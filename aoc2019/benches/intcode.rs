@@ -0,0 +1,40 @@
+//! Compare the plain interpreter with the decode cache on day 9 part 2, the most
+//! instruction-heavy program so far.
+//!
+//! Run with `cargo bench --bench intcode`.
+
+use aoc2019::intcode::{parse_program, IntcodeComputer};
+use std::fs;
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 20;
+
+/// Run BOOST in sensor boost mode `RUNS` times, returning the average time per run.
+fn time(program: &[i64], decode_cache: bool) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..RUNS {
+        let mut computer = IntcodeComputer::new(program);
+        computer.set_decode_cache(decode_cache);
+        computer.push_input(2);
+        computer.run().expect("BOOST failed");
+        assert_eq!(computer.output().len(), 1);
+    }
+
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let source = fs::read_to_string("input/2019/day9.txt").expect("could not read day 9 input");
+    let program = parse_program(&source);
+
+    // Warm up caches and the CPU before measuring.
+    time(&program, false);
+
+    let plain = time(&program, false);
+    let cached = time(&program, true);
+
+    println!("interpreter:   {plain:?} per run");
+    println!("decode cache:  {cached:?} per run");
+    println!("speedup:       {:.2}x", plain.as_secs_f64() / cached.as_secs_f64());
+}
@@ -0,0 +1,43 @@
+//! Watch the day 13 arcade game play itself.
+//!
+//! Usage: arcade PROGRAM_FILE [FRAME_MILLISECONDS]
+//!
+//! Each frame is drawn over the last one; the default is 20ms per frame.
+
+use aoc2019::day_13::play;
+use aoc2019::intcode::parse_program;
+use std::time::Duration;
+use std::{env, fs, process, thread};
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let Some(path) = args.next() else {
+        eprintln!("usage: arcade PROGRAM_FILE [FRAME_MILLISECONDS]");
+        process::exit(2);
+    };
+    let frame = args
+        .next()
+        .map(|ms| {
+            ms.parse()
+                .expect("frame time must be a number of milliseconds")
+        })
+        .unwrap_or(20);
+    let source = fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("could not read {path}: {e}");
+        process::exit(1);
+    });
+
+    let result = play(&parse_program(&source), |screen| {
+        // Clear the terminal and move the cursor home before drawing.
+        print!("\x1b[2J\x1b[H{screen}");
+        thread::sleep(Duration::from_millis(frame));
+    });
+
+    match result {
+        Ok(score) => println!("final score: {score}"),
+        Err(e) => {
+            eprintln!("error: {e}");
+            process::exit(1);
+        }
+    }
+}
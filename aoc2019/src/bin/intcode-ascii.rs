@@ -0,0 +1,26 @@
+//! Play a text-based Intcode program from the terminal.
+//!
+//! Usage: intcode-ascii PROGRAM_FILE
+//!
+//! Everything the program prints is shown, and each line typed is sent to it as a command.
+
+use aoc2019::intcode::ascii::AsciiComputer;
+use aoc2019::intcode::parse_program;
+use std::{env, fs, io, process};
+
+fn main() {
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("usage: intcode-ascii PROGRAM_FILE");
+        process::exit(2);
+    };
+    let source = fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("could not read {path}: {e}");
+        process::exit(1);
+    });
+
+    let mut computer = AsciiComputer::new(&parse_program(&source));
+    if let Err(e) = computer.interact(io::stdin().lock(), io::stdout()) {
+        eprintln!("error: {e}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,198 @@
+//! Interactive debugger for Intcode programs.
+//!
+//! Usage: intcode-debug PROGRAM_FILE
+//!
+//! Type `help` at the prompt for the list of commands.
+
+use aoc2019::intcode::debugger::{Debugger, Stop};
+use aoc2019::intcode::{parse_program, Event, IntcodeComputer, IntcodeError};
+use std::io::{self, BufRead, Write};
+use std::{env, fs, process};
+
+const HELP: &str = "\
+commands:
+  s, step [N]          execute N instructions (default 1)
+  c, continue          run until a breakpoint, watchpoint, input request or halt
+  b, break ADDR        set a breakpoint
+  d, delete ADDR       remove a breakpoint
+  w, watch ADDR        stop when the value at ADDR changes
+  u, unwatch ADDR      remove a watchpoint
+  i, input N...        queue input values
+  r, regs              show the instruction pointer, relative base and next instruction
+  x ADDR [LEN]         dump LEN memory cells starting at ADDR (default 8)
+  l, list [ADDR] [LEN] disassemble LEN instructions from ADDR (default: 8 from the ip)
+  o, output            show everything output so far
+  h, help              show this message
+  q, quit              exit";
+
+/// The most cells `x` dumps or instructions `list` shows at once, so a mistyped length can't
+/// exhaust memory or flood the terminal.
+const MAX_LEN: usize = 4096;
+
+fn main() {
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("usage: intcode-debug PROGRAM_FILE");
+        process::exit(2);
+    };
+    let source = fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("could not read {path}: {e}");
+        process::exit(1);
+    });
+
+    let mut debugger = Debugger::new(IntcodeComputer::new(&parse_program(&source)));
+    let mut shown_output = 0;
+
+    print_registers(&debugger);
+    prompt();
+
+    for line in io::stdin().lock().lines() {
+        let line = line.expect("could not read standard input");
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or("");
+        let args: Vec<i64> = match words.map(str::parse).collect() {
+            Ok(args) => args,
+            Err(_) => {
+                println!("arguments must be integers");
+                prompt();
+                continue;
+            }
+        };
+        let addr = |n: usize| args.get(n).and_then(|a| usize::try_from(*a).ok());
+
+        match command {
+            "s" | "step" => {
+                for _ in 0..args.first().copied().unwrap_or(1).max(1) {
+                    let stop = report(debugger.step());
+                    if !matches!(stop, Some(Stop::Stepped | Stop::Event(Event::Output(_)))) {
+                        break;
+                    }
+                }
+                print_registers(&debugger);
+            }
+            "c" | "continue" => {
+                report(debugger.resume());
+                print_registers(&debugger);
+            }
+            "b" | "break" => match addr(0) {
+                Some(a) => {
+                    debugger.add_breakpoint(a);
+                }
+                None => println!("usage: break ADDR"),
+            },
+            "d" | "delete" => match addr(0) {
+                Some(a) if debugger.remove_breakpoint(a) => {}
+                _ => println!("no breakpoint there"),
+            },
+            "w" | "watch" => match addr(0) {
+                Some(a) => {
+                    debugger.add_watchpoint(a);
+                }
+                None => println!("usage: watch ADDR"),
+            },
+            "u" | "unwatch" => match addr(0) {
+                Some(a) if debugger.remove_watchpoint(a) => {}
+                _ => println!("no watchpoint there"),
+            },
+            "i" | "input" => {
+                for value in &args {
+                    debugger.computer_mut().push_input(*value);
+                }
+            }
+            "r" | "regs" => print_registers(&debugger),
+            "x" => match (addr(0), addr(1).unwrap_or(8)) {
+                (Some(_), len) if len > MAX_LEN => println!("LEN can be at most {MAX_LEN}"),
+                (Some(start), len) => match debugger.dump(start, len) {
+                    Some(cells) => {
+                        for (i, chunk) in cells.chunks(8).enumerate() {
+                            let values: Vec<String> = chunk.iter().map(i64::to_string).collect();
+                            println!("{:04}: {}", start + i * 8, values.join(", "));
+                        }
+                    }
+                    None => println!("that runs past the highest address"),
+                },
+                (None, _) => println!("usage: x ADDR [LEN]"),
+            },
+            "l" | "list" => match addr(1).unwrap_or(8) {
+                len if len > MAX_LEN => println!("LEN can be at most {MAX_LEN}"),
+                len => {
+                    let mut a = addr(0).unwrap_or(debugger.computer().ip());
+                    for _ in 0..len {
+                        let size = match debugger.disassemble_at(a) {
+                            Some(item) => {
+                                println!("{item}");
+                                item.size()
+                            }
+                            None => {
+                                println!("{a:04}: .data {}", debugger.computer().read(a));
+                                1
+                            }
+                        };
+                        match a.checked_add(size) {
+                            Some(next) => a = next,
+                            None => break,
+                        }
+                    }
+                }
+            },
+            "o" | "output" => println!("{:?}", debugger.computer().output()),
+            "h" | "help" => println!("{HELP}"),
+            "q" | "quit" => break,
+            "" => {}
+            _ => println!("unknown command `{command}`, try `help`"),
+        }
+
+        // Show anything the program output since the last command.
+        let output = debugger.computer().output();
+        for value in &output[shown_output..] {
+            println!("> {value}");
+        }
+        shown_output = output.len();
+
+        prompt();
+    }
+}
+
+fn prompt() {
+    print!("(intcode) ");
+    io::stdout().flush().unwrap();
+}
+
+/// Describe why execution stopped.
+fn report(result: Result<Stop, IntcodeError>) -> Option<Stop> {
+    match result {
+        Ok(stop) => {
+            match stop {
+                Stop::Stepped | Stop::Event(Event::Output(_)) => {}
+                Stop::Breakpoint(addr) => println!("breakpoint at {addr}"),
+                Stop::Watchpoint { addr, old, new } => {
+                    println!("watchpoint: [{addr}] changed from {old} to {new}")
+                }
+                Stop::Event(Event::NeedsInput) => println!("waiting for input"),
+                Stop::Event(Event::Halted) => println!("halted"),
+            }
+            Some(stop)
+        }
+        Err(e) => {
+            println!("error: {e}");
+            None
+        }
+    }
+}
+
+fn print_registers(debugger: &Debugger) {
+    let computer = debugger.computer();
+    let next = match debugger.disassemble_at(computer.ip()) {
+        Some(item) => item.to_string(),
+        None => format!(
+            "{:04}: .data {}",
+            computer.ip(),
+            computer.read(computer.ip())
+        ),
+    };
+
+    println!(
+        "ip={} rb={}  {next}",
+        computer.ip(),
+        computer.relative_base()
+    );
+}
@@ -0,0 +1,48 @@
+//! Print a disassembly listing of an Intcode program.
+//!
+//! Usage: intcode-dis [--dot] [PROGRAM_FILE]
+//!
+//! Reads the program from standard input when no file is given. With `--dot`, prints the
+//! program's control-flow graph in Graphviz DOT format instead, and any warnings from analysing
+//! it to standard error.
+
+use aoc2019::intcode::cfg::Cfg;
+use aoc2019::intcode::{disasm, parse_program};
+use std::io::Read;
+use std::{env, fs, io, process};
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let dot = match args.iter().position(|arg| arg == "--dot") {
+        Some(i) => {
+            args.remove(i);
+            true
+        }
+        None => false,
+    };
+
+    let source = match args.first() {
+        Some(path) => fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("could not read {path}: {e}");
+            process::exit(1);
+        }),
+        None => {
+            let mut source = String::new();
+            io::stdin()
+                .read_to_string(&mut source)
+                .expect("could not read standard input");
+            source
+        }
+    };
+    let program = parse_program(&source);
+
+    if dot {
+        let cfg = Cfg::new(&program);
+        for warning in cfg.warnings() {
+            eprintln!("warning: {warning}");
+        }
+        print!("{}", cfg.to_dot());
+    } else {
+        print!("{}", disasm::listing(&program));
+    }
+}
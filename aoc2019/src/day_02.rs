@@ -1,22 +1,21 @@
+use crate::intcode::search::Search;
+use crate::intcode::symbolic::SymbolicComputer;
+use crate::intcode::{parse_program, IntcodeComputer, IntcodeError};
+use itertools::Itertools;
+use std::error::Error;
 use std::fmt;
 
 #[aoc_generator(day2)]
-fn input_generator(input: &str) -> Vec<i32> {
-    input.split(',').map(|n| {n.parse::<i32>().unwrap()}).collect()
+fn input_generator(input: &str) -> Vec<i64> {
+    parse_program(input)
 }
 
 #[aoc(day2, part1)]
-fn p1(input: &[i32]) -> i32 {
-    let mut program = input.to_owned();
-    program[1] = 12;
-    program[2] = 2;
-
-    run(&mut program);
-
-    program[0]
+fn p1(input: &[i64]) -> Result<i64, IntcodeError> {
+    run(&mut IntcodeComputer::new(input), 12, 2)
 }
 
-struct Pair(i32, i32);
+struct Pair(i64, i64);
 
 impl fmt::Display for Pair {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -25,48 +24,51 @@ impl fmt::Display for Pair {
 }
 
 #[aoc(day2, part2)]
-fn p2(input: &[i32]) -> Option<Pair> {
-    const ANSWER: i32 = 19690720;
+fn p2(input: &[i64]) -> Option<Pair> {
+    const ANSWER: i64 = 19690720;
+    const BUDGET: u64 = 100_000;
 
-    for i in 0..=99 {
-        for j in 0..=99 {
-            let mut program = input.to_owned();
-            program[1] = i;
-            program[2] = j;
-            run(&mut program);
+    let patches: Vec<[(usize, i64); 2]> = (0..=99)
+        .cartesian_product(0..=99)
+        .map(|(noun, verb)| [(1, noun), (2, verb)])
+        .collect();
 
-            match program[0].cmp(&ANSWER) {
-                std::cmp::Ordering::Less => continue,
-                std::cmp::Ordering::Equal => return Some(Pair(i, j)),
-                std::cmp::Ordering::Greater => break,
-            }
-        }
-    }
+    // Some noun/verb pairs make the program misbehave, they just aren't the answer. That includes
+    // patching in a jump that loops forever, so each candidate gets a budget to run out of.
+    let mut base = IntcodeComputer::new(input);
+    base.set_budget(Some(BUDGET));
 
-    None
+    let (patch, _) =
+        Search::new().first_patched(&base, &patches, |c| (c.read(0) == ANSWER).then_some(()))?;
+
+    Some(Pair(patch[0].1, patch[1].1))
 }
 
-fn run(program: &mut [i32]) {
-    let mut pc: usize = 0; // program counter
+/**
+ * Solve part 2 algebraically: run the program once with the noun and verb left as symbols, then
+ * solve the expression left at address 0 for the answer.
+ */
+#[aoc(day2, part2, symbolic)]
+fn p2_symbolic(input: &[i64]) -> Result<Pair, Box<dyn Error>> {
+    let mut computer = SymbolicComputer::new(input, &[(1, "noun"), (2, "verb")]);
+    computer.run()?;
 
-    loop {
-        match program[pc] {
-            1 => {
-                let a = program[pc+1] as usize;
-                let b = program[pc+2] as usize;
-                let dest = program[pc+3] as usize;
-                program[dest] = program[a] + program[b];
-                pc += 4;
-            },
-            2 => {
-                let a = program[pc+1] as usize;
-                let b = program[pc+2] as usize;
-                let dest = program[pc+3] as usize;
-                program[dest] = program[a] * program[b];
-                pc += 4;
-            },
-            99 => break,
-            _ => panic!("invalid opcode"),
-        }
-    }
-}
\ No newline at end of file
+    let output = computer.read(0);
+    let output = output.as_expr().ok_or("output depends on an unknown value")?;
+    let values = output
+        .solve(19690720, &[("noun", 0..=99), ("verb", 0..=99)])
+        .ok_or_else(|| format!("no noun and verb make {output} equal 19690720"))?;
+
+    Ok(Pair(values[0], values[1]))
+}
+
+/**
+ * Run the loaded program with the given noun and verb, returning the value left at address 0.
+ */
+fn run(computer: &mut IntcodeComputer, noun: i64, verb: i64) -> Result<i64, IntcodeError> {
+    computer.write(1, noun);
+    computer.write(2, verb);
+    computer.run()?;
+
+    Ok(computer.read(0))
+}
@@ -0,0 +1,151 @@
+use crate::intcode::{parse_program, Event, IntcodeComputer, IntcodeError};
+use std::collections::HashMap;
+use std::fmt;
+
+type Point = (i32, i32);
+
+const BLACK: i64 = 0;
+const WHITE: i64 = 1;
+
+#[aoc_generator(day11)]
+fn input_generator(input: &str) -> Vec<i64> {
+    parse_program(input)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    /// The panel one step away in this direction. Y grows downwards, like rows on a screen.
+    fn step(self, (x, y): Point) -> Point {
+        match self {
+            Direction::Up => (x, y - 1),
+            Direction::Right => (x + 1, y),
+            Direction::Down => (x, y + 1),
+            Direction::Left => (x - 1, y),
+        }
+    }
+}
+
+/**
+ * Wait for the robot's next output, or `None` if it halted instead. The robot always outputs a
+ * colour and a turn for each panel it's shown, so asking for more input first is an error.
+ */
+fn next_output(robot: &mut IntcodeComputer) -> Result<Option<i64>, IntcodeError> {
+    match robot.run_until_event()? {
+        Event::Output(value) => Ok(Some(value)),
+        Event::Halted => Ok(None),
+        Event::NeedsInput => Err(IntcodeError::InputExhausted {
+            ip: robot.ip(),
+            instruction: robot.read(robot.ip()),
+        }),
+    }
+}
+
+/**
+ * Run the painting robot from a hull whose starting panel is `start_color` and every other panel
+ * is black. Returns the colour of every panel the robot painted at least once.
+ */
+fn paint(program: &[i64], start_color: i64) -> Result<HashMap<Point, i64>, IntcodeError> {
+    let mut robot = IntcodeComputer::new(program);
+    let mut painted = HashMap::new();
+    let mut position = (0, 0);
+    let mut direction = Direction::Up;
+
+    loop {
+        let default = if position == (0, 0) { start_color } else { BLACK };
+        robot.push_input(painted.get(&position).copied().unwrap_or(default));
+
+        let Some(color) = next_output(&mut robot)? else {
+            break;
+        };
+        let Some(turn) = next_output(&mut robot)? else {
+            break;
+        };
+
+        painted.insert(position, color);
+        direction = if turn == 0 {
+            direction.turn_left()
+        } else {
+            direction.turn_right()
+        };
+        position = direction.step(position);
+    }
+
+    Ok(painted)
+}
+
+#[aoc(day11, part1)]
+fn p1(input: &[i64]) -> Result<usize, IntcodeError> {
+    Ok(paint(input, BLACK)?.len())
+}
+
+/**
+ * The white panels of a painted hull, cropped to the area they cover.
+ */
+struct Hull(HashMap<Point, i64>);
+
+impl fmt::Display for Hull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let white: Vec<Point> = self
+            .0
+            .iter()
+            .filter(|(_, color)| **color == WHITE)
+            .map(|(point, _)| *point)
+            .collect();
+
+        let (Some(min_x), Some(max_x)) = (
+            white.iter().map(|p| p.0).min(),
+            white.iter().map(|p| p.0).max(),
+        ) else {
+            return Ok(());
+        };
+        let min_y = white.iter().map(|p| p.1).min().unwrap();
+        let max_y = white.iter().map(|p| p.1).max().unwrap();
+
+        let mut image_output = String::new();
+
+        for y in min_y..=max_y {
+            image_output.push('\n');
+
+            for x in min_x..=max_x {
+                image_output.push(match self.0.get(&(x, y)) {
+                    Some(&WHITE) => 'X',
+                    _ => ' ',
+                });
+
+                image_output.push(' ');
+            }
+        }
+
+        write!(f, "{image_output}")
+    }
+}
+
+#[aoc(day11, part2)]
+fn p2(input: &[i64]) -> Result<Hull, IntcodeError> {
+    Ok(Hull(paint(input, WHITE)?))
+}
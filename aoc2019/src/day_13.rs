@@ -0,0 +1,169 @@
+use crate::intcode::{parse_program, Event, IntcodeComputer};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+type Point = (i64, i64);
+
+#[aoc_generator(day13)]
+fn input_generator(input: &str) -> Vec<i64> {
+    parse_program(input)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Empty,
+    Wall,
+    Block,
+    Paddle,
+    Ball,
+}
+
+impl TryFrom<i64> for Tile {
+    type Error = String;
+
+    fn try_from(id: i64) -> Result<Self, Self::Error> {
+        match id {
+            0 => Ok(Tile::Empty),
+            1 => Ok(Tile::Wall),
+            2 => Ok(Tile::Block),
+            3 => Ok(Tile::Paddle),
+            4 => Ok(Tile::Ball),
+            _ => Err(format!("unknown tile id {id}")),
+        }
+    }
+}
+
+/**
+ * The arcade cabinet's screen and score display.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Screen {
+    tiles: HashMap<Point, Tile>,
+    score: i64,
+    ball: Option<Point>,
+    paddle: Option<Point>,
+}
+
+impl Screen {
+    /**
+     * Apply one `(x, y, value)` instruction from the game. Position (-1, 0) sets the score
+     * instead of a tile.
+     */
+    fn draw(&mut self, x: i64, y: i64, value: i64) -> Result<(), String> {
+        if (x, y) == (-1, 0) {
+            self.score = value;
+            return Ok(());
+        }
+
+        let tile = Tile::try_from(value)?;
+        match tile {
+            Tile::Ball => self.ball = Some((x, y)),
+            Tile::Paddle => self.paddle = Some((x, y)),
+            _ => {}
+        }
+        self.tiles.insert((x, y), tile);
+
+        Ok(())
+    }
+
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    pub fn blocks(&self) -> usize {
+        self.tiles.values().filter(|t| **t == Tile::Block).count()
+    }
+}
+
+impl fmt::Display for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.tiles.keys().map(|p| p.0).max().unwrap_or(0);
+        let height = self.tiles.keys().map(|p| p.1).max().unwrap_or(0);
+
+        writeln!(f, "score: {}", self.score)?;
+        for y in 0..=height {
+            let row: String = (0..=width)
+                .map(|x| match self.tiles.get(&(x, y)) {
+                    Some(Tile::Wall) => '#',
+                    Some(Tile::Block) => '=',
+                    Some(Tile::Paddle) => '-',
+                    Some(Tile::Ball) => 'o',
+                    Some(Tile::Empty) | None => ' ',
+                })
+                .collect();
+            writeln!(f, "{row}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * Run the game until it wants joystick input or halts, drawing everything it outputs. Returns
+ * whether the game halted.
+ */
+fn run_frame(cabinet: &mut IntcodeComputer, screen: &mut Screen) -> Result<bool, Box<dyn Error>> {
+    let halted = loop {
+        match cabinet.run_until_event()? {
+            Event::Output(_) => {}
+            Event::NeedsInput => break false,
+            Event::Halted => break true,
+        }
+    };
+
+    let output = cabinet.output_mut();
+    let complete = output.len() - output.len() % 3;
+    for triple in output.drain(..complete).collect::<Vec<_>>().chunks(3) {
+        screen.draw(triple[0], triple[1], triple[2])?;
+    }
+
+    Ok(halted)
+}
+
+#[aoc(day13, part1)]
+fn p1(input: &[i64]) -> Result<usize, Box<dyn Error>> {
+    let mut cabinet = IntcodeComputer::new(input);
+    let mut screen = Screen::default();
+
+    if !run_frame(&mut cabinet, &mut screen)? {
+        return Err("the game asked for input before any quarters were inserted".into());
+    }
+
+    Ok(screen.blocks())
+}
+
+/**
+ * Play the game for free, keeping the paddle under the ball until every block is broken. `watch`
+ * is shown the screen each time the game waits for the joystick.
+ *
+ * Returns the final score.
+ */
+pub fn play(program: &[i64], mut watch: impl FnMut(&Screen)) -> Result<i64, Box<dyn Error>> {
+    let mut cabinet = IntcodeComputer::new(program);
+    let mut screen = Screen::default();
+
+    // Two quarters for free play.
+    cabinet.write(0, 2);
+
+    while !run_frame(&mut cabinet, &mut screen)? {
+        watch(&screen);
+
+        let (Some(ball), Some(paddle)) = (screen.ball, screen.paddle) else {
+            return Err("the game wants input before drawing the ball and paddle".into());
+        };
+        cabinet.push_input((ball.0 - paddle.0).signum());
+    }
+
+    watch(&screen);
+
+    match screen.blocks() {
+        0 => Ok(screen.score),
+        blocks => Err(format!("lost the game with {blocks} blocks left").into()),
+    }
+}
+
+#[aoc(day13, part2)]
+fn p2(input: &[i64]) -> Result<i64, Box<dyn Error>> {
+    play(input, |_| {})
+}
@@ -0,0 +1,164 @@
+use crate::intcode::{parse_program, Event, IntcodeComputer, Snapshot};
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+type Point = (i32, i32);
+
+#[aoc_generator(day15)]
+fn input_generator(input: &str) -> Vec<i64> {
+    parse_program(input)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Wall,
+    Open,
+    Oxygen,
+}
+
+/**
+ * Movement commands in the order the droid numbers them: north, south, west and east.
+ */
+const MOVES: [(i64, Point); 4] = [(1, (0, -1)), (2, (0, 1)), (3, (-1, 0)), (4, (1, 0))];
+
+fn neighbours((x, y): Point) -> impl Iterator<Item = (i64, Point)> {
+    MOVES
+        .into_iter()
+        .map(move |(command, (dx, dy))| (command, (x + dx, y + dy)))
+}
+
+/**
+ * The section of the ship the droid has explored, relative to where it started.
+ */
+struct Map(HashMap<Point, Cell>);
+
+impl Map {
+    fn oxygen(&self) -> Option<Point> {
+        self.0
+            .iter()
+            .find(|(_, cell)| **cell == Cell::Oxygen)
+            .map(|(point, _)| *point)
+    }
+
+    /**
+     * The number of moves from `start` to every open cell it can reach.
+     */
+    fn distances(&self, start: Point) -> HashMap<Point, usize> {
+        let mut distances = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[&point];
+
+            for (_, next) in neighbours(point) {
+                let open = matches!(self.0.get(&next), Some(Cell::Open | Cell::Oxygen));
+                if open && !distances.contains_key(&next) {
+                    distances.insert(next, distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        distances
+    }
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let min_x = self.0.keys().map(|p| p.0).min().unwrap_or(0);
+        let max_x = self.0.keys().map(|p| p.0).max().unwrap_or(0);
+        let min_y = self.0.keys().map(|p| p.1).min().unwrap_or(0);
+        let max_y = self.0.keys().map(|p| p.1).max().unwrap_or(0);
+
+        for y in min_y..=max_y {
+            writeln!(f)?;
+
+            for x in min_x..=max_x {
+                let c = match self.0.get(&(x, y)) {
+                    _ if (x, y) == (0, 0) => 'D',
+                    Some(Cell::Wall) => '#',
+                    Some(Cell::Open) => '.',
+                    Some(Cell::Oxygen) => 'O',
+                    None => ' ',
+                };
+                write!(f, "{c}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * Map every cell the droid can reach, breadth first. Instead of walking the droid back and forth,
+ * each reached cell keeps a snapshot of the droid standing there, so trying a move from it only
+ * means restoring that snapshot.
+ */
+fn explore(program: &[i64]) -> Result<Map, Box<dyn Error>> {
+    let mut droid = IntcodeComputer::new(program);
+    let mut map = HashMap::from([((0, 0), Cell::Open)]);
+    let mut queue: VecDeque<(Point, Snapshot)> = VecDeque::from([((0, 0), droid.snapshot())]);
+
+    while let Some((point, snapshot)) = queue.pop_front() {
+        for (command, next) in neighbours(point) {
+            if map.contains_key(&next) {
+                continue;
+            }
+
+            droid.restore(&snapshot);
+            droid.push_input(command);
+            let status = match droid.run_until_event()? {
+                Event::Output(status) => status,
+                event => {
+                    return Err(format!("droid stopped with {event:?} instead of moving").into())
+                }
+            };
+
+            let cell = match status {
+                0 => Cell::Wall,
+                1 => Cell::Open,
+                2 => Cell::Oxygen,
+                _ => return Err(format!("unknown status code {status}").into()),
+            };
+            map.insert(next, cell);
+
+            if cell != Cell::Wall {
+                // The statuses so far aren't needed, so don't copy them into every snapshot.
+                droid.output_mut().clear();
+                queue.push_back((next, droid.snapshot()));
+            }
+        }
+    }
+
+    Ok(Map(map))
+}
+
+#[aoc(day15, part1)]
+fn p1(input: &[i64]) -> Result<usize, Box<dyn Error>> {
+    let map = explore(input)?;
+    let oxygen = map
+        .oxygen()
+        .ok_or("the droid never found the oxygen system")?;
+
+    Ok(map.distances((0, 0))[&oxygen])
+}
+
+/**
+ * Part 1, showing the map the droid explored instead of the answer.
+ */
+#[aoc(day15, part1, map)]
+fn p1_map(input: &[i64]) -> Result<Map, Box<dyn Error>> {
+    explore(input)
+}
+
+#[aoc(day15, part2)]
+fn p2(input: &[i64]) -> Result<usize, Box<dyn Error>> {
+    let map = explore(input)?;
+    let oxygen = map
+        .oxygen()
+        .ok_or("the droid never found the oxygen system")?;
+
+    // Oxygen spreads one cell a minute, so the last cell filled is the furthest from the system.
+    Ok(map.distances(oxygen).into_values().max().unwrap_or(0))
+}
@@ -0,0 +1,57 @@
+use crate::intcode::network::{Network, NetworkEvent, Topology};
+use crate::intcode::parse_program;
+use std::error::Error;
+
+const COMPUTERS: usize = 50;
+const NAT: i64 = 255;
+
+#[aoc_generator(day23)]
+fn input_generator(input: &str) -> Vec<i64> {
+    parse_program(input)
+}
+
+/**
+ * Boot the network: every computer runs the same program and learns its address from its first
+ * input. The scheduler runs the computers in turn, so the order packets arrive in is always the
+ * same.
+ */
+fn boot(program: &[i64]) -> Network {
+    Network::new(program, COMPUTERS, Topology::Packets { nat: NAT })
+}
+
+#[aoc(day23, part1)]
+fn p1(input: &[i64]) -> Result<i64, Box<dyn Error>> {
+    let mut network = boot(input);
+
+    loop {
+        match network.run_until_event()? {
+            NetworkEvent::NatReceived(packet) => return Ok(packet.y),
+            NetworkEvent::Halted | NetworkEvent::Deadlocked => {
+                return Err("the network stopped before sending anything to the NAT".into())
+            }
+            _ => {}
+        }
+    }
+}
+
+#[aoc(day23, part2)]
+fn p2(input: &[i64]) -> Result<i64, Box<dyn Error>> {
+    let mut network = boot(input);
+    let mut last_y = None;
+
+    loop {
+        match network.run_until_event()? {
+            NetworkEvent::NatSent(packet) => {
+                if last_y == Some(packet.y) {
+                    return Ok(packet.y);
+                }
+                last_y = Some(packet.y);
+            }
+            NetworkEvent::Halted => return Err("the network halted".into()),
+            NetworkEvent::Deadlocked => {
+                return Err("the network went idle before the NAT received a packet".into())
+            }
+            _ => {}
+        }
+    }
+}
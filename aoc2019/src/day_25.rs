@@ -0,0 +1,226 @@
+use crate::intcode::ascii::{AsciiComputer, Response};
+use crate::intcode::parse_program;
+use std::collections::HashSet;
+use std::error::Error;
+
+#[aoc_generator(day25)]
+fn input_generator(input: &str) -> Vec<i64> {
+    parse_program(input)
+}
+
+/**
+ * Items that end the game or trap the droid when picked up.
+ */
+const DANGEROUS_ITEMS: [&str; 5] = [
+    "infinite loop",
+    "giant electromagnet",
+    "molten lava",
+    "escape pod",
+    "photons",
+];
+
+const CHECKPOINT: &str = "Security Checkpoint";
+
+/**
+ * Enough instructions for the droid to answer any command. Only an item we didn't know was
+ * dangerous should use them up.
+ */
+const COMMAND_BUDGET: u64 = 10_000_000;
+
+/**
+ * A room as the droid describes it.
+ */
+#[derive(Debug)]
+struct Room {
+    name: String,
+    doors: Vec<String>,
+    items: Vec<String>,
+}
+
+/**
+ * Parse the last room described in `text`. Being ejected from the pressure-sensitive floor
+ * describes both the floor and the room the droid lands in, and the droid is in the second.
+ */
+fn parse_room(text: &str) -> Option<Room> {
+    let start = text.rfind("== ")?;
+    let mut lines = text[start..].lines();
+
+    let name = lines
+        .next()?
+        .trim_matches(|c| c == '=' || c == ' ')
+        .to_string();
+    let mut room = Room {
+        name,
+        doors: vec![],
+        items: vec![],
+    };
+
+    let mut list = None;
+    for line in lines {
+        match line {
+            "Doors here lead:" => list = Some(&mut room.doors),
+            "Items here:" => list = Some(&mut room.items),
+            _ => match (line.strip_prefix("- "), list.as_mut()) {
+                (Some(entry), Some(list)) => list.push(entry.to_string()),
+                _ => list = None,
+            },
+        }
+    }
+
+    Some(room)
+}
+
+fn opposite(direction: &str) -> &'static str {
+    match direction {
+        "north" => "south",
+        "south" => "north",
+        "east" => "west",
+        _ => "east",
+    }
+}
+
+struct Droid {
+    computer: AsciiComputer,
+    inventory: Vec<String>,
+    visited: HashSet<String>,
+    checkpoint: Option<(Vec<String>, String)>, // the way to the checkpoint, and its way onwards
+}
+
+impl Droid {
+    fn command(&mut self, command: &str) -> Result<Response, Box<dyn Error>> {
+        self.computer
+            .computer_mut()
+            .set_budget(Some(COMMAND_BUDGET));
+        Ok(self.computer.execute(command)?)
+    }
+
+    /**
+     * Move through a door, returning the room on the other side.
+     */
+    fn go(&mut self, direction: &str) -> Result<Room, Box<dyn Error>> {
+        let response = self.command(direction)?;
+        parse_room(&response.text)
+            .ok_or_else(|| format!("couldn't go {direction}: {}", response.text).into())
+    }
+
+    /**
+     * Explore every room reachable from `room`, depth first, picking up every safe item on the
+     * way and coming back to `room` at the end. `path` is the way here from the start.
+     *
+     * The only door not taken is the one from the security checkpoint, since going through it
+     * without the right items just gets the droid sent back.
+     */
+    fn explore(&mut self, room: Room, path: &mut Vec<String>) -> Result<(), Box<dyn Error>> {
+        self.visited.insert(room.name.clone());
+
+        for item in &room.items {
+            if DANGEROUS_ITEMS.contains(&item.as_str()) {
+                continue;
+            }
+
+            let response = self.command(&format!("take {item}"))?;
+            if response.halted {
+                return Err(format!("picking up the {item} ended the game").into());
+            }
+            self.inventory.push(item.clone());
+        }
+
+        let back = path.last().map(|d| opposite(d));
+
+        if room.name == CHECKPOINT {
+            let onwards = room
+                .doors
+                .iter()
+                .find(|door| Some(door.as_str()) != back)
+                .ok_or("the security checkpoint is a dead end")?;
+            self.checkpoint = Some((path.clone(), onwards.clone()));
+            return Ok(());
+        }
+
+        for door in &room.doors {
+            if Some(door.as_str()) == back {
+                continue;
+            }
+
+            let next = self.go(door)?;
+            if !self.visited.contains(&next.name) {
+                path.push(door.clone());
+                self.explore(next, path)?;
+                path.pop();
+            }
+            self.go(opposite(door))?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * From the checkpoint, try stepping onto the pressure-sensitive floor holding each
+     * combination of items until one weighs the right amount. Combinations are tried in Gray code
+     * order, so only one item is dropped or taken between attempts.
+     *
+     * Returns what the droid said when it got through.
+     */
+    fn pass_checkpoint(&mut self, onwards: &str) -> Result<String, Box<dyn Error>> {
+        let items = self.inventory.clone();
+        let all = (1u32 << items.len()) - 1;
+        let mut holding = all;
+
+        for i in 0..=all {
+            let wanted = all ^ i ^ (i >> 1);
+
+            let changed = holding ^ wanted;
+            if changed != 0 {
+                let item = &items[changed.trailing_zeros() as usize];
+                let verb = if wanted & changed != 0 {
+                    "take"
+                } else {
+                    "drop"
+                };
+                self.command(&format!("{verb} {item}"))?;
+                holding = wanted;
+            }
+
+            let response = self.command(onwards)?;
+            if response.halted {
+                return Ok(response.text);
+            }
+        }
+
+        Err("no combination of items got past the pressure-sensitive floor".into())
+    }
+}
+
+/**
+ * Pick out the number the droid is told to type at the airlock.
+ */
+fn password(text: &str) -> Option<u64> {
+    let after = &text[text.find("typing ")? + "typing ".len()..];
+    let digits: String = after.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
+
+#[aoc(day25, part1)]
+fn p1(input: &[i64]) -> Result<u64, Box<dyn Error>> {
+    let mut droid = Droid {
+        computer: AsciiComputer::new(input),
+        inventory: vec![],
+        visited: HashSet::new(),
+        checkpoint: None,
+    };
+
+    let start = droid.computer.run()?;
+    let start = parse_room(&start.text).ok_or("the droid didn't describe where it is")?;
+    droid.explore(start, &mut vec![])?;
+
+    let (path, onwards) = droid
+        .checkpoint
+        .take()
+        .ok_or("the droid never found the security checkpoint")?;
+    for direction in &path {
+        droid.go(direction)?;
+    }
+
+    let text = droid.pass_checkpoint(&onwards)?;
+    password(&text).ok_or_else(|| format!("no password in: {text}").into())
+}
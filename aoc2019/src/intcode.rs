@@ -0,0 +1,394 @@
+//! Shared Intcode computer used by every 2019 puzzle that runs an Intcode program.
+
+pub mod ascii;
+pub mod asm;
+mod cache;
+pub mod cfg;
+pub mod debugger;
+pub mod disasm;
+mod error;
+mod instruction;
+mod io;
+mod memory;
+pub mod network;
+pub mod search;
+pub mod symbolic;
+mod snapshot;
+pub mod trace;
+
+pub use error::IntcodeError;
+pub use instruction::{Instruction, Opcode, ParamMode};
+pub use io::{AsciiInput, AsciiOutput, IntcodeInput, IntcodeOutput, IterInput};
+pub use memory::Memory;
+pub use snapshot::Snapshot;
+use cache::{DecodeCache, Decoded};
+use std::collections::VecDeque;
+use trace::{TraceEntry, TraceSink};
+
+/// Parse a comma-separated Intcode program.
+pub fn parse_program(input: &str) -> Vec<i64> {
+    input
+        .trim()
+        .split(',')
+        .map(|n| n.parse::<i64>().unwrap())
+        .collect()
+}
+
+/// Something that happened during execution which the caller needs to act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The machine is stopped at an input instruction and its input has nothing to give. Provide
+    /// some (e.g. with [`IntcodeComputer::push_input`]) and resume.
+    NeedsInput,
+    /// The machine output a value. The value has already been written to the machine's output.
+    Output(i64),
+    /// The machine reached a halt instruction. Running it again keeps reporting `Halted`.
+    Halted,
+}
+
+/// An Intcode machine reading input from `I` and writing output to `O`.
+///
+/// By default input is a queue filled with [`IntcodeComputer::push_input`] and output is collected
+/// into a `Vec`; use [`IntcodeComputer::with_io`] for anything else.
+///
+/// Arithmetic that overflows an `i64` fails with [`IntcodeError::Overflow`] rather than giving a
+/// wrong answer.
+#[derive(Debug, Clone)]
+pub struct IntcodeComputer<I = VecDeque<i64>, O = Vec<i64>> {
+    mem: Memory,        // program memory, including intcode instructions
+    ip: usize,          // instruction pointer
+    relative_base: i64, // base address for relative mode parameters
+    input: I,
+    output: O,
+    budget: Option<u64>, // instructions left to execute, if limited
+    cache: Option<DecodeCache>,
+}
+
+impl IntcodeComputer {
+    pub fn new(program: &[i64]) -> Self {
+        Self::with_io(program, VecDeque::new(), vec![])
+    }
+}
+
+impl<O> IntcodeComputer<VecDeque<i64>, O> {
+    /// Queue a value for the program's next input instruction.
+    pub fn push_input(&mut self, value: i64) {
+        self.input.push_back(value);
+    }
+}
+
+impl<I: IntcodeInput, O: IntcodeOutput> IntcodeComputer<I, O> {
+    pub fn with_io(program: &[i64], input: I, output: O) -> Self {
+        IntcodeComputer {
+            mem: Memory::new(program),
+            ip: 0,
+            relative_base: 0,
+            input,
+            output,
+            budget: None,
+            cache: None,
+        }
+    }
+
+    pub fn input(&self) -> &I {
+        &self.input
+    }
+
+    pub fn input_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    pub fn output(&self) -> &O {
+        &self.output
+    }
+
+    pub fn output_mut(&mut self) -> &mut O {
+        &mut self.output
+    }
+
+    /// Consume the machine, returning its input and output.
+    pub fn into_io(self) -> (I, O) {
+        (self.input, self.output)
+    }
+
+    /// The address of the next instruction to execute.
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// The base address relative mode parameters are offset from.
+    pub fn relative_base(&self) -> i64 {
+        self.relative_base
+    }
+
+    /// The machine's whole memory.
+    pub fn memory(&self) -> &Memory {
+        &self.mem
+    }
+
+    /// The number of instructions the machine may still execute, if limited.
+    pub fn budget(&self) -> Option<u64> {
+        self.budget
+    }
+
+    /// Limit the machine to executing `budget` more instructions, or lift the limit with `None`.
+    /// Once the budget is used up, stepping fails with [`IntcodeError::BudgetExhausted`], so a
+    /// program stuck in an infinite loop can't hang its caller.
+    pub fn set_budget(&mut self, budget: Option<u64>) {
+        self.budget = budget;
+    }
+
+    /// Turn caching of decoded instructions on or off. With the cache on, each instruction is
+    /// decoded only the first time it executes, which speeds up programs that spend their time in
+    /// loops. Writes to cached instructions are detected, so self-modifying programs behave the
+    /// same either way.
+    pub fn set_decode_cache(&mut self, enabled: bool) {
+        self.cache = enabled.then(DecodeCache::default);
+    }
+
+    /// Read the value stored at `addr`.
+    pub fn read(&self, addr: usize) -> i64 {
+        self.mem.read(addr)
+    }
+
+    /// Overwrite the value stored at `addr`, e.g. to patch a program before running it.
+    pub fn write(&mut self, addr: usize, value: i64) {
+        self.mem.write(addr, value);
+        if let Some(cache) = &mut self.cache {
+            cache.invalidate(addr);
+        }
+    }
+
+    /// The raw value of the instruction at the instruction pointer.
+    fn instruction(&self) -> i64 {
+        self.mem.read(self.ip)
+    }
+
+    /// Decode the instruction at the instruction pointer, or fetch it from the cache.
+    fn decode(&mut self) -> Result<Decoded, IntcodeError> {
+        if let Some(decoded) = self.cache.as_ref().and_then(|c| c.get(self.ip)) {
+            return Ok(decoded);
+        }
+
+        let ins = Instruction::decode(self.ip, self.instruction())?;
+        let mut params = [0; 3];
+        for (n, param) in params.iter_mut().enumerate().take(ins.size() - 1) {
+            *param = self.mem.read(self.ip + n + 1);
+        }
+
+        let decoded = Decoded { ins, params };
+        // Instructions stored sparsely, far past the rest of the program, are decoded every time
+        // rather than growing the cache out to their address.
+        let dense = self.ip < self.mem.dense().len();
+        if let Some(cache) = self.cache.as_mut().filter(|_| dense) {
+            cache.insert(self.ip, decoded);
+        }
+        Ok(decoded)
+    }
+
+    /// The error for an instruction whose arithmetic overflows an `i64`.
+    fn overflow(&self) -> IntcodeError {
+        IntcodeError::Overflow {
+            ip: self.ip,
+            instruction: self.instruction(),
+        }
+    }
+
+    /// Convert a computed address into an index into memory.
+    fn check_address(&self, address: i64) -> Result<usize, IntcodeError> {
+        usize::try_from(address).map_err(|_| IntcodeError::NegativeAddress {
+            ip: self.ip,
+            instruction: self.instruction(),
+            address,
+        })
+    }
+
+    /// Resolve the address that parameter `n` (1-based) of the current instruction refers to.
+    /// Immediate mode parameters have no address, so they can't be written to.
+    fn address(&self, decoded: &Decoded, n: usize) -> Result<usize, IntcodeError> {
+        let param = decoded.params[n - 1];
+        let address = match decoded.ins.mode(n) {
+            ParamMode::Position => param,
+            ParamMode::Relative => self
+                .relative_base
+                .checked_add(param)
+                .ok_or_else(|| self.overflow())?,
+            ParamMode::Immediate => {
+                return Err(IntcodeError::ImmediateWrite {
+                    ip: self.ip,
+                    instruction: self.instruction(),
+                    param: n as u32,
+                })
+            }
+        };
+
+        self.check_address(address)
+    }
+
+    /// Read the value of parameter `n` (1-based) of the current instruction.
+    fn param(&self, decoded: &Decoded, n: usize) -> Result<i64, IntcodeError> {
+        match decoded.ins.mode(n) {
+            ParamMode::Immediate => Ok(decoded.params[n - 1]),
+            _ => Ok(self.mem.read(self.address(decoded, n)?)),
+        }
+    }
+
+    /// Execute a single instruction.
+    ///
+    /// Returns the event the instruction raised, if any. When the program needs input that hasn't
+    /// been queued, or has halted, the instruction pointer doesn't move, so stepping again after
+    /// pushing input resumes where it left off.
+    pub fn step(&mut self) -> Result<Option<Event>, IntcodeError> {
+        self.step_traced(&mut ())
+    }
+
+    /// Execute a single instruction like [`IntcodeComputer::step`], recording it to `sink`. An
+    /// input instruction that has to wait for input isn't recorded until it executes.
+    pub fn step_traced<T: TraceSink>(
+        &mut self,
+        sink: &mut T,
+    ) -> Result<Option<Event>, IntcodeError> {
+        let ip = self.ip;
+        if self.budget == Some(0) {
+            return Err(IntcodeError::BudgetExhausted {
+                ip,
+                instruction: self.instruction(),
+            });
+        }
+
+        let decoded = self.decode()?;
+        let ins = decoded.ins;
+        let mut operands = [0; 3];
+        let mut written = None;
+        let mut next = ip + ins.size();
+        let mut event = None;
+
+        match ins.opcode {
+            Opcode::Add => {
+                let (a, b) = (self.param(&decoded, 1)?, self.param(&decoded, 2)?);
+                operands = [a, b, 0];
+                let dest = self.address(&decoded, 3)?;
+                written = Some((dest, a.checked_add(b).ok_or_else(|| self.overflow())?));
+            }
+
+            Opcode::Multiply => {
+                let (a, b) = (self.param(&decoded, 1)?, self.param(&decoded, 2)?);
+                operands = [a, b, 0];
+                let dest = self.address(&decoded, 3)?;
+                written = Some((dest, a.checked_mul(b).ok_or_else(|| self.overflow())?));
+            }
+
+            Opcode::Input => {
+                let dest = self.address(&decoded, 1)?;
+                let Some(value) = self.input.read() else {
+                    return Ok(Some(Event::NeedsInput));
+                };
+                written = Some((dest, value));
+            }
+
+            Opcode::Output => {
+                let value = self.param(&decoded, 1)?;
+                operands = [value, 0, 0];
+                self.output.write(value);
+                event = Some(Event::Output(value));
+            }
+
+            Opcode::JumpIfTrue => {
+                let (condition, target) = (self.param(&decoded, 1)?, self.param(&decoded, 2)?);
+                operands = [condition, target, 0];
+                if condition != 0 {
+                    next = self.check_address(target)?;
+                }
+            }
+
+            Opcode::JumpIfFalse => {
+                let (condition, target) = (self.param(&decoded, 1)?, self.param(&decoded, 2)?);
+                operands = [condition, target, 0];
+                if condition == 0 {
+                    next = self.check_address(target)?;
+                }
+            }
+
+            Opcode::LessThan => {
+                let (a, b) = (self.param(&decoded, 1)?, self.param(&decoded, 2)?);
+                operands = [a, b, 0];
+                written = Some((self.address(&decoded, 3)?, i64::from(a < b)));
+            }
+
+            Opcode::Equals => {
+                let (a, b) = (self.param(&decoded, 1)?, self.param(&decoded, 2)?);
+                operands = [a, b, 0];
+                written = Some((self.address(&decoded, 3)?, i64::from(a == b)));
+            }
+
+            Opcode::AdjustRelativeBase => {
+                let offset = self.param(&decoded, 1)?;
+                operands = [offset, 0, 0];
+                self.relative_base = self
+                    .relative_base
+                    .checked_add(offset)
+                    .ok_or_else(|| self.overflow())?;
+            }
+
+            Opcode::Halt => {
+                next = ip;
+                event = Some(Event::Halted);
+            }
+        }
+
+        if let Some((dest, value)) = written {
+            self.write(dest, value);
+        }
+        self.ip = next;
+        if let Some(budget) = &mut self.budget {
+            *budget -= 1;
+        }
+
+        sink.record(&TraceEntry {
+            ip,
+            instruction: ins,
+            operands,
+            written,
+        });
+
+        Ok(event)
+    }
+
+    /// Execute instructions until the program outputs a value, needs input or halts.
+    pub fn run_until_event(&mut self) -> Result<Event, IntcodeError> {
+        self.run_until_event_traced(&mut ())
+    }
+
+    /// Like [`IntcodeComputer::run_until_event`], recording every executed instruction to `sink`.
+    pub fn run_until_event_traced<T: TraceSink>(
+        &mut self,
+        sink: &mut T,
+    ) -> Result<Event, IntcodeError> {
+        loop {
+            if let Some(event) = self.step_traced(sink)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Run the program until it halts. Running out of input before then is an error.
+    pub fn run(&mut self) -> Result<(), IntcodeError> {
+        self.run_traced(&mut ())
+    }
+
+    /// Like [`IntcodeComputer::run`], recording every executed instruction to `sink`.
+    pub fn run_traced<T: TraceSink>(&mut self, sink: &mut T) -> Result<(), IntcodeError> {
+        loop {
+            match self.run_until_event_traced(sink)? {
+                Event::Output(_) => {}
+                Event::Halted => return Ok(()),
+                Event::NeedsInput => {
+                    return Err(IntcodeError::InputExhausted {
+                        ip: self.ip,
+                        instruction: self.instruction(),
+                    })
+                }
+            }
+        }
+    }
+}
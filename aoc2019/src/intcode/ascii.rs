@@ -0,0 +1,106 @@
+//! Talk to programs that read and write ASCII text, like the text-based 2019 puzzles.
+
+use super::{AsciiInput, AsciiOutput, Event, IntcodeComputer, IntcodeError};
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+/// What a program output between one request for input and the next.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Response {
+    pub text: String,
+    /// The last value output that isn't an ASCII character. Programs output their answer this
+    /// way, usually after the text.
+    pub answer: Option<i64>,
+    /// Whether the program halted, rather than stopping to wait for a command.
+    pub halted: bool,
+}
+
+impl Response {
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.text.lines()
+    }
+}
+
+/// A machine driven by lines of text.
+#[derive(Debug, Clone)]
+pub struct AsciiComputer {
+    computer: IntcodeComputer<AsciiInput, AsciiOutput>,
+}
+
+impl AsciiComputer {
+    pub fn new(program: &[i64]) -> Self {
+        AsciiComputer {
+            computer: IntcodeComputer::with_io(program, AsciiInput::new(), AsciiOutput::new()),
+        }
+    }
+
+    pub fn computer(&self) -> &IntcodeComputer<AsciiInput, AsciiOutput> {
+        &self.computer
+    }
+
+    /// Access the machine directly, e.g. to patch memory or take a snapshot.
+    pub fn computer_mut(&mut self) -> &mut IntcodeComputer<AsciiInput, AsciiOutput> {
+        &mut self.computer
+    }
+
+    /// Queue `command` followed by a newline. It's read when the program next runs.
+    pub fn send(&mut self, command: &str) {
+        self.computer.input_mut().push_line(command);
+    }
+
+    /// Run until the program wants a command that hasn't been sent, or halts, returning what it
+    /// output in the meantime.
+    pub fn run(&mut self) -> Result<Response, IntcodeError> {
+        let halted = loop {
+            match self.computer.run_until_event()? {
+                Event::Output(_) => {}
+                Event::NeedsInput => break false,
+                Event::Halted => break true,
+            }
+        };
+
+        let output = self.computer.output_mut();
+        Ok(Response {
+            text: output.take_text(),
+            answer: output.take_non_ascii().last().copied(),
+            halted,
+        })
+    }
+
+    /// Send `command` and run until the program wants the next one.
+    pub fn execute(&mut self, command: &str) -> Result<Response, IntcodeError> {
+        self.send(command);
+        self.run()
+    }
+
+    /// Play the program interactively: print its output to `output` and send it each line read
+    /// from `input`, until it halts or `input` runs out. Returns the program's answer, if it
+    /// gave one.
+    pub fn interact<R: BufRead, W: Write>(
+        &mut self,
+        input: R,
+        mut output: W,
+    ) -> Result<Option<i64>, Box<dyn Error>> {
+        let mut lines = input.lines();
+        let mut answer = None;
+
+        loop {
+            let response = self.run()?;
+            write!(output, "{}", response.text)?;
+            if let Some(value) = response.answer {
+                writeln!(output, "{value}")?;
+                answer = Some(value);
+            }
+            output.flush()?;
+
+            if response.halted {
+                return Ok(answer);
+            }
+
+            let Some(line) = lines.next() else {
+                return Ok(answer);
+            };
+            self.send(&line?);
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! Assemble Intcode programs from the mnemonic syntax used by [`disasm`](super::disasm).
+//!
+//! ```text
+//! ; Output the numbers 5 down to 1.
+//!         add  #5, #0, [count]
+//! loop:   out  [count]
+//!         add  [count], #-1, [count]
+//!         jnz  [count], #loop
+//!         hlt
+//! count:  .data 0
+//! ```
+//!
+//! Each line holds an optional label (`name:`), then an instruction or `.data` directive, then an
+//! optional `;` comment. The address prefixes written by the disassembler (`0012:`) are accepted and
+//! ignored, so a listing assembles back into the program it came from.
+//!
+//! Operands are `[x]` for position mode, `#x` for immediate mode and `rb+x`/`rb-x` for relative
+//! mode, where `x` is a number, a label or a sum of them (`#table+2`).
+
+use super::{Instruction, Opcode, ParamMode};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssembleErrorKind {
+    UnknownMnemonic(String),
+    UnknownDirective(String),
+    WrongOperandCount {
+        expected: usize,
+        found: usize,
+    },
+    InvalidOperand(String),
+    InvalidLabel(String),
+    DuplicateLabel(String),
+    UndefinedLabel(String),
+    /// The instruction writes to an operand given in immediate mode.
+    ImmediateWrite(String),
+    /// The expression's value doesn't fit in an `i64`.
+    Overflow(String),
+}
+
+/// An error in assembly source, with the 1-based line number it was found on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssembleError {
+    pub line: usize,
+    pub kind: AssembleErrorKind,
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match &self.kind {
+            AssembleErrorKind::UnknownMnemonic(m) => write!(f, "unknown mnemonic `{m}`"),
+            AssembleErrorKind::UnknownDirective(d) => write!(f, "unknown directive `{d}`"),
+            AssembleErrorKind::WrongOperandCount { expected, found } => {
+                write!(f, "expected {expected} operands, found {found}")
+            }
+            AssembleErrorKind::InvalidOperand(o) => write!(f, "invalid operand `{o}`"),
+            AssembleErrorKind::InvalidLabel(l) => write!(f, "invalid label `{l}`"),
+            AssembleErrorKind::DuplicateLabel(l) => write!(f, "label `{l}` is already defined"),
+            AssembleErrorKind::UndefinedLabel(l) => write!(f, "undefined label `{l}`"),
+            AssembleErrorKind::ImmediateWrite(o) => {
+                write!(f, "operand `{o}` is written to and can't be immediate")
+            }
+            AssembleErrorKind::Overflow(e) => write!(f, "`{e}` overflows"),
+        }
+    }
+}
+
+impl std::error::Error for AssembleError {}
+
+/// A sum of numbers and labels, resolved once every label's address is known.
+#[derive(Debug, Clone)]
+struct Expr {
+    source: String, // as written, for errors
+    terms: Vec<(i64, Term)>,
+}
+
+#[derive(Debug, Clone)]
+enum Term {
+    Number(i64),
+    Label(String),
+}
+
+impl Expr {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let mut terms = vec![];
+        let mut sign = 1;
+        let mut start = 0;
+
+        // Split on + and -, remembering the sign in front of each term.
+        for (i, c) in s.char_indices().chain([(s.len(), '+')]) {
+            if c != '+' && c != '-' {
+                continue;
+            }
+
+            let term = s[start..i].trim();
+            if term.is_empty() {
+                // Only a leading sign may be missing its term, e.g. `-5`.
+                if i != 0 || i == s.len() {
+                    return None;
+                }
+            } else if let Ok(n) = format!("{}{term}", if sign < 0 { "-" } else { "" }).parse() {
+                // Parse a number along with its sign, so that `-9223372036854775808` fits.
+                terms.push((1, Term::Number(n)));
+            } else {
+                terms.push((sign, Term::parse(term)?));
+            }
+
+            sign = if c == '-' { -1 } else { 1 };
+            start = i + 1;
+        }
+
+        Some(Expr {
+            source: s.to_string(),
+            terms,
+        })
+    }
+
+    fn resolve(&self, labels: &HashMap<String, usize>) -> Result<i64, AssembleErrorKind> {
+        self.terms.iter().try_fold(0i64, |sum, (sign, term)| {
+            let value = match term {
+                Term::Number(n) => *n,
+                Term::Label(label) => *labels
+                    .get(label)
+                    .ok_or_else(|| AssembleErrorKind::UndefinedLabel(label.clone()))?
+                    as i64,
+            };
+
+            value
+                .checked_mul(*sign)
+                .and_then(|value| sum.checked_add(value))
+                .ok_or_else(|| AssembleErrorKind::Overflow(self.source.clone()))
+        })
+    }
+}
+
+impl Term {
+    fn parse(s: &str) -> Option<Self> {
+        if let Ok(n) = s.parse::<i64>() {
+            Some(Term::Number(n))
+        } else if is_label(s) {
+            Some(Term::Label(s.to_string()))
+        } else {
+            None
+        }
+    }
+}
+
+fn is_label(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[derive(Debug)]
+struct Operand {
+    mode: ParamMode,
+    value: Expr,
+}
+
+impl Operand {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+
+        let (mode, value) = if let Some(inner) = s.strip_prefix('[') {
+            (ParamMode::Position, Expr::parse(inner.strip_suffix(']')?)?)
+        } else if let Some(value) = s.strip_prefix('#') {
+            (ParamMode::Immediate, Expr::parse(value)?)
+        } else if let Some(offset) = s.strip_prefix("rb") {
+            let offset = offset.trim_start();
+            if offset.is_empty() {
+                (ParamMode::Relative, Expr::parse("0")?)
+            } else if offset.starts_with(['+', '-']) {
+                // A leading `+` isn't accepted by the expression parser on its own.
+                let offset = offset.strip_prefix('+').unwrap_or(offset);
+                (ParamMode::Relative, Expr::parse(offset)?)
+            } else {
+                return None;
+            }
+        } else {
+            return None;
+        };
+
+        Some(Operand { mode, value })
+    }
+}
+
+/// A parsed line that emits something into the program.
+#[derive(Debug)]
+enum Statement {
+    Instruction {
+        opcode: Opcode,
+        operands: Vec<Operand>,
+    },
+    Data(Vec<Expr>),
+}
+
+impl Statement {
+    fn size(&self) -> usize {
+        match self {
+            Statement::Instruction { operands, .. } => 1 + operands.len(),
+            Statement::Data(values) => values.len(),
+        }
+    }
+}
+
+/// Split a line into comma-separated operands, ignoring an empty operand list.
+fn split_operands(s: &str) -> Vec<&str> {
+    if s.trim().is_empty() {
+        vec![]
+    } else {
+        s.split(',').map(str::trim).collect()
+    }
+}
+
+fn parse_statement(s: &str) -> Result<Statement, AssembleErrorKind> {
+    let (word, rest) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+    let raw_operands = split_operands(rest);
+
+    if let Some(directive) = word.strip_prefix('.') {
+        if directive != "data" {
+            return Err(AssembleErrorKind::UnknownDirective(word.to_string()));
+        }
+
+        let values = raw_operands
+            .iter()
+            .map(|o| Expr::parse(o).ok_or_else(|| AssembleErrorKind::InvalidOperand(o.to_string())))
+            .collect::<Result<_, _>>()?;
+        return Ok(Statement::Data(values));
+    }
+
+    let opcode = Opcode::from_mnemonic(word)
+        .ok_or_else(|| AssembleErrorKind::UnknownMnemonic(word.to_string()))?;
+
+    if raw_operands.len() != opcode.param_count() {
+        return Err(AssembleErrorKind::WrongOperandCount {
+            expected: opcode.param_count(),
+            found: raw_operands.len(),
+        });
+    }
+
+    let operands = raw_operands
+        .iter()
+        .map(|o| Operand::parse(o).ok_or_else(|| AssembleErrorKind::InvalidOperand(o.to_string())))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(n) = opcode.write_param() {
+        if operands[n - 1].mode == ParamMode::Immediate {
+            return Err(AssembleErrorKind::ImmediateWrite(
+                raw_operands[n - 1].to_string(),
+            ));
+        }
+    }
+
+    Ok(Statement::Instruction { opcode, operands })
+}
+
+/// Assemble source into a program that [`IntcodeComputer`](super::IntcodeComputer) can load.
+pub fn assemble(source: &str) -> Result<Vec<i64>, AssembleError> {
+    let mut labels: HashMap<String, usize> = HashMap::new();
+    let mut statements = vec![];
+    let mut addr = 0;
+
+    // First pass: parse each line and find the address of every label.
+    for (i, line) in source.lines().enumerate() {
+        let error = |kind| AssembleError { line: i + 1, kind };
+        let mut rest = line.split(';').next().unwrap().trim();
+
+        while let Some((label, after)) = rest.split_once(':') {
+            let label = label.trim();
+
+            // Addresses written by the disassembler.
+            if !label.is_empty() && label.chars().all(|c| c.is_ascii_digit()) {
+                rest = after.trim();
+                continue;
+            }
+
+            if !is_label(label) {
+                return Err(error(AssembleErrorKind::InvalidLabel(label.to_string())));
+            }
+            if labels.insert(label.to_string(), addr).is_some() {
+                return Err(error(AssembleErrorKind::DuplicateLabel(label.to_string())));
+            }
+            rest = after.trim();
+        }
+
+        if rest.is_empty() {
+            continue;
+        }
+
+        let statement = parse_statement(rest).map_err(error)?;
+        addr += statement.size();
+        statements.push((i + 1, statement));
+    }
+
+    // Second pass: emit the program now that labels can be resolved.
+    let mut program = Vec::with_capacity(addr);
+    for (line, statement) in statements {
+        let error = |kind| AssembleError { line, kind };
+
+        match statement {
+            Statement::Instruction { opcode, operands } => {
+                let mut modes = [ParamMode::Position; 3];
+                for (mode, operand) in modes.iter_mut().zip(&operands) {
+                    *mode = operand.mode;
+                }
+                program.push(Instruction { opcode, modes }.encode());
+
+                for operand in operands {
+                    program.push(operand.value.resolve(&labels).map_err(error)?);
+                }
+            }
+            Statement::Data(values) => {
+                for value in values {
+                    program.push(value.resolve(&labels).map_err(error)?);
+                }
+            }
+        }
+    }
+
+    Ok(program)
+}
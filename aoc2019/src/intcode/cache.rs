@@ -0,0 +1,43 @@
+use super::Instruction;
+
+/// An instruction decoded along with the raw values of its parameters.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Decoded {
+    pub ins: Instruction,
+    pub params: [i64; 3],
+}
+
+/// Instructions decoded so far, by address, so a loop only decodes its body once.
+///
+/// A write anywhere within a cached instruction drops it, so programs that modify their own code
+/// still run the code they wrote. Only addresses in memory's dense region are cached, so the cache
+/// never grows much past the program itself.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DecodeCache {
+    entries: Vec<Option<Decoded>>,
+}
+
+/// The most cells an instruction covers: its opcode and three parameters.
+const MAX_SIZE: usize = 4;
+
+impl DecodeCache {
+    pub fn get(&self, addr: usize) -> Option<Decoded> {
+        self.entries.get(addr).copied().flatten()
+    }
+
+    pub fn insert(&mut self, addr: usize, decoded: Decoded) {
+        if addr >= self.entries.len() {
+            self.entries.resize(addr + 1, None);
+        }
+        self.entries[addr] = Some(decoded);
+    }
+
+    /// Forget every cached instruction covering `addr`.
+    pub fn invalidate(&mut self, addr: usize) {
+        let start = addr.saturating_sub(MAX_SIZE - 1);
+        let end = self.entries.len().min(addr.saturating_add(1));
+        if start < end {
+            self.entries[start..end].fill(None);
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! Control-flow graphs of Intcode programs, and the problems static analysis can spot in them.
+//!
+//! The graph covers the code [`disasm::reachable_instructions`] finds, so it has the same
+//! limits: jumps through memory can't be followed, and code that's rewritten at run time is
+//! analysed as it is before the program starts.
+
+use super::disasm::{self, instruction_at};
+use super::{Instruction, Opcode, ParamMode};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::ops::Range;
+
+/// A run of instructions that always execute in sequence: only the first is jumped to and only
+/// the last can jump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    /// The addresses of the instructions in the block, in order.
+    pub instructions: Vec<usize>,
+    /// The start addresses of the blocks control can move to next.
+    pub successors: Vec<usize>,
+    /// Whether the block ends in a jump whose target is read from memory, so it may have
+    /// successors that aren't listed.
+    pub indirect_exit: bool,
+}
+
+impl BasicBlock {
+    pub fn start(&self) -> usize {
+        self.instructions[0]
+    }
+}
+
+/// Something suspicious about a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warning {
+    /// The instruction at `addr` writes to `target`, which is part of the instruction at
+    /// `instruction` or where control moves to an invalid instruction, so the program modifies
+    /// its own code.
+    SelfModifying {
+        addr: usize,
+        target: usize,
+        instruction: usize,
+    },
+    /// The instruction at `addr` jumps to an address read from memory, so the graph may be
+    /// missing code.
+    IndirectJump { addr: usize },
+    /// Control can move from the instruction at `addr` to `target`, which doesn't hold a valid
+    /// instruction yet. Unless the program writes one there first it will fail.
+    InvalidTarget { addr: usize, target: usize },
+    /// The cells from `start` up to `end` decode as a run of valid instructions, but no reachable
+    /// instruction leads to them. They're dead code, or only reached through an indirect jump.
+    UnreachableCode { start: usize, end: usize },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::SelfModifying {
+                addr,
+                target,
+                instruction,
+            } => write!(
+                f,
+                "{addr:04}: writes to [{target}], part of the instruction at {instruction:04}"
+            ),
+            Warning::IndirectJump { addr } => {
+                write!(f, "{addr:04}: jumps to an address read from memory")
+            }
+            Warning::InvalidTarget { addr, target } => {
+                write!(
+                    f,
+                    "{addr:04}: continues at {target:04}, which isn't a valid instruction"
+                )
+            }
+            Warning::UnreachableCode { start, end } => {
+                write!(
+                    f,
+                    "{start:04}-{end:04}: looks like code but is never reached"
+                )
+            }
+        }
+    }
+}
+
+/// The control-flow graph of a program.
+#[derive(Debug, Clone)]
+pub struct Cfg {
+    program: Vec<i64>,
+    blocks: BTreeMap<usize, BasicBlock>, // keyed by start address
+    warnings: Vec<Warning>,
+}
+
+impl Cfg {
+    /// Analyse the program as it is before it starts running.
+    pub fn new(program: &[i64]) -> Self {
+        let starts = disasm::reachable_instructions(program);
+        let decode = |addr: usize| Instruction::decode(addr, program[addr]).unwrap();
+
+        // Blocks start at the beginning of the program, at jump targets and after jumps.
+        let mut leaders = BTreeSet::from([0]);
+        for &addr in &starts {
+            let ins = decode(addr);
+            if let Opcode::JumpIfTrue | Opcode::JumpIfFalse | Opcode::Halt = ins.opcode {
+                leaders.extend(disasm::successors(program, addr, &ins));
+                leaders.insert(addr + ins.size());
+            }
+        }
+
+        // Where control can go that the disassembler couldn't decode as an instruction, most
+        // likely because the program writes the instruction there as it runs.
+        let invalid: BTreeSet<usize> = starts
+            .iter()
+            .flat_map(|&addr| disasm::successors(program, addr, &decode(addr)))
+            .filter(|addr| !starts.contains(addr))
+            .collect();
+
+        let mut blocks = BTreeMap::new();
+        let mut warnings = vec![];
+        let mut current: Vec<usize> = vec![];
+
+        for &addr in &starts {
+            let ins = decode(addr);
+            current.push(addr);
+
+            let next = addr + ins.size();
+            let is_jump = matches!(ins.opcode, Opcode::JumpIfTrue | Opcode::JumpIfFalse);
+            let may_jump = ins.mode(1) != ParamMode::Immediate
+                || (program[addr + 1] != 0) == (ins.opcode == Opcode::JumpIfTrue);
+            let indirect_exit = is_jump && may_jump && ins.mode(2) != ParamMode::Immediate;
+            if indirect_exit {
+                warnings.push(Warning::IndirectJump { addr });
+            }
+
+            if let Some(n) = ins.opcode.write_param() {
+                if ins.mode(n) == ParamMode::Position {
+                    let target = program[addr + n];
+                    let written = usize::try_from(target).ok().and_then(|target| {
+                        let instruction = instruction_containing(&starts, program, target)
+                            .or_else(|| invalid.contains(&target).then_some(target))?;
+                        Some((target, instruction))
+                    });
+                    if let Some((target, instruction)) = written {
+                        warnings.push(Warning::SelfModifying {
+                            addr,
+                            target,
+                            instruction,
+                        });
+                    }
+                }
+            }
+
+            if is_jump
+                || ins.opcode == Opcode::Halt
+                || leaders.contains(&next)
+                || !starts.contains(&next)
+            {
+                let successors = match ins.opcode {
+                    Opcode::JumpIfTrue | Opcode::JumpIfFalse | Opcode::Halt => {
+                        disasm::successors(program, addr, &ins)
+                    }
+                    _ => vec![next],
+                };
+
+                for &target in successors.iter().filter(|s| invalid.contains(s)) {
+                    warnings.push(Warning::InvalidTarget { addr, target });
+                }
+
+                let instructions = std::mem::take(&mut current);
+                blocks.insert(
+                    instructions[0],
+                    BasicBlock {
+                        instructions,
+                        successors: successors
+                            .into_iter()
+                            .filter(|s| starts.contains(s))
+                            .collect(),
+                        indirect_exit,
+                    },
+                );
+            }
+        }
+
+        let mut cfg = Cfg {
+            program: program.to_vec(),
+            blocks,
+            warnings,
+        };
+        for range in cfg.unreachable() {
+            if decodes_as_code(program, range.clone()) {
+                cfg.warnings.push(Warning::UnreachableCode {
+                    start: range.start,
+                    end: range.end,
+                });
+            }
+        }
+
+        cfg
+    }
+
+    /// The basic blocks, in address order.
+    pub fn blocks(&self) -> impl Iterator<Item = &BasicBlock> {
+        self.blocks.values()
+    }
+
+    /// The block starting at `addr`.
+    pub fn block(&self, addr: usize) -> Option<&BasicBlock> {
+        self.blocks.get(&addr)
+    }
+
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// The ranges of cells that aren't part of any reachable instruction: data, or code that can
+    /// only be reached through a jump the analysis can't follow.
+    pub fn unreachable(&self) -> Vec<Range<usize>> {
+        let mut ranges = vec![];
+        let mut addr = 0;
+
+        for block in self.blocks() {
+            for &start in &block.instructions {
+                if start > addr {
+                    ranges.push(addr..start);
+                }
+                addr = start
+                    + Instruction::decode(start, self.program[start])
+                        .unwrap()
+                        .size();
+            }
+        }
+        if addr < self.program.len() {
+            ranges.push(addr..self.program.len());
+        }
+
+        ranges
+    }
+
+    /// Render the graph in Graphviz DOT format, one node per block labelled with its listing.
+    /// Blocks that modify code are outlined in red, and jumps the analysis can't follow point to
+    /// a node marked `?`.
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph intcode {\n    node [shape=box, fontname=monospace];\n");
+        let modifying: BTreeSet<usize> = self
+            .warnings
+            .iter()
+            .filter_map(|warning| match warning {
+                Warning::SelfModifying { addr, .. } => Some(*addr),
+                Warning::IndirectJump { .. }
+                | Warning::InvalidTarget { .. }
+                | Warning::UnreachableCode { .. } => None,
+            })
+            .collect();
+
+        for block in self.blocks() {
+            let label: String = block
+                .instructions
+                .iter()
+                .map(|&addr| {
+                    format!(
+                        "{}\\l",
+                        instruction_at(&self.program[addr..], addr).unwrap()
+                    )
+                })
+                .collect();
+            let color = if block.instructions.iter().any(|a| modifying.contains(a)) {
+                ", color=red"
+            } else {
+                ""
+            };
+            dot += &format!("    b{} [label=\"{label}\"{color}];\n", block.start());
+
+            for successor in &block.successors {
+                dot += &format!("    b{} -> b{successor};\n", block.start());
+            }
+            if block.indirect_exit {
+                dot += &format!("    b{} -> unknown [style=dashed];\n", block.start());
+            }
+        }
+
+        if self.blocks().any(|block| block.indirect_exit) {
+            dot += "    unknown [label=\"?\", shape=circle];\n";
+        }
+
+        dot += "}\n";
+        dot
+    }
+}
+
+/// Whether the cells in `range` decode, one after another, as valid instructions that exactly
+/// fill it.
+fn decodes_as_code(program: &[i64], range: Range<usize>) -> bool {
+    let mut addr = range.start;
+    while addr < range.end {
+        match instruction_at(&program[addr..range.end], addr) {
+            Some(item) => addr += item.size(),
+            None => return false,
+        }
+    }
+    true
+}
+
+/// The start of the reachable instruction covering `addr`, if there is one.
+fn instruction_containing(starts: &BTreeSet<usize>, program: &[i64], addr: usize) -> Option<usize> {
+    let start = *starts.range(..=addr).next_back()?;
+    let size = Instruction::decode(start, program[start]).ok()?.size();
+    (addr < start + size).then_some(start)
+}
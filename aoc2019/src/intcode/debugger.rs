@@ -0,0 +1,132 @@
+//! Breakpoints, watchpoints and single-stepping over an [`IntcodeComputer`].
+
+use super::disasm::{self, Item};
+use super::{Event, IntcodeComputer, IntcodeError, IntcodeInput, IntcodeOutput};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Why the debugger handed control back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// A single step finished without anything noteworthy happening.
+    Stepped,
+    /// The instruction pointer reached a breakpoint. The instruction there hasn't executed yet.
+    Breakpoint(usize),
+    /// A watched memory cell changed value.
+    Watchpoint { addr: usize, old: i64, new: i64 },
+    /// The machine raised an event. Outputs only stop single steps; when resuming they're left in
+    /// the machine's output.
+    Event(Event),
+}
+
+pub struct Debugger<I = VecDeque<i64>, O = Vec<i64>> {
+    computer: IntcodeComputer<I, O>,
+    breakpoints: BTreeSet<usize>,
+    watchpoints: BTreeMap<usize, i64>, // watched address -> last value seen there
+}
+
+impl<I: IntcodeInput, O: IntcodeOutput> Debugger<I, O> {
+    pub fn new(computer: IntcodeComputer<I, O>) -> Self {
+        Debugger {
+            computer,
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeMap::new(),
+        }
+    }
+
+    pub fn computer(&self) -> &IntcodeComputer<I, O> {
+        &self.computer
+    }
+
+    /// Access the machine directly, e.g. to push input or patch memory.
+    pub fn computer_mut(&mut self) -> &mut IntcodeComputer<I, O> {
+        &mut self.computer
+    }
+
+    pub fn into_computer(self) -> IntcodeComputer<I, O> {
+        self.computer
+    }
+
+    /// Break before executing the instruction at `addr`. Returns false if there already was one.
+    pub fn add_breakpoint(&mut self, addr: usize) -> bool {
+        self.breakpoints.insert(addr)
+    }
+
+    pub fn remove_breakpoint(&mut self, addr: usize) -> bool {
+        self.breakpoints.remove(&addr)
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    /// Stop whenever the value at `addr` changes. Returns false if it was already watched.
+    pub fn add_watchpoint(&mut self, addr: usize) -> bool {
+        let value = self.computer.read(addr);
+        self.watchpoints.insert(addr, value).is_none()
+    }
+
+    pub fn remove_watchpoint(&mut self, addr: usize) -> bool {
+        self.watchpoints.remove(&addr).is_some()
+    }
+
+    pub fn watchpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.watchpoints.keys().copied()
+    }
+
+    /// Execute a single instruction.
+    pub fn step(&mut self) -> Result<Stop, IntcodeError> {
+        let event = self.computer.step()?;
+
+        if let Some(stop) = self.check_watchpoints() {
+            return Ok(stop);
+        }
+
+        Ok(event.map_or(Stop::Stepped, Stop::Event))
+    }
+
+    /// Run until a breakpoint or watchpoint is hit, or the machine needs input or halts. At least
+    /// one instruction is executed, so resuming from a breakpoint moves past it.
+    pub fn resume(&mut self) -> Result<Stop, IntcodeError> {
+        loop {
+            match self.step()? {
+                Stop::Stepped | Stop::Event(Event::Output(_)) => {}
+                stop => return Ok(stop),
+            }
+
+            if self.breakpoints.contains(&self.computer.ip()) {
+                return Ok(Stop::Breakpoint(self.computer.ip()));
+            }
+        }
+    }
+
+    /// Report the first watched cell whose value changed since it was last checked.
+    fn check_watchpoints(&mut self) -> Option<Stop> {
+        let mut stop = None;
+
+        for (&addr, last) in self.watchpoints.iter_mut() {
+            let value = self.computer.read(addr);
+            if value != *last {
+                stop = stop.or(Some(Stop::Watchpoint {
+                    addr,
+                    old: *last,
+                    new: value,
+                }));
+                *last = value;
+            }
+        }
+
+        stop
+    }
+
+    /// The values of `len` memory cells starting at `start`, or `None` if they would run past the
+    /// highest address.
+    pub fn dump(&self, start: usize, len: usize) -> Option<Vec<i64>> {
+        let end = start.checked_add(len)?;
+        Some((start..end).map(|a| self.computer.read(a)).collect())
+    }
+
+    /// Disassemble the instruction at `addr` as it currently is in memory.
+    pub fn disassemble_at(&self, addr: usize) -> Option<Item> {
+        disasm::instruction_at(&self.dump(addr, 4)?, addr)
+    }
+}
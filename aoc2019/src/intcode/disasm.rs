@@ -0,0 +1,210 @@
+//! Turn Intcode programs back into readable listings.
+
+use super::{Instruction, Opcode, ParamMode};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// The most data values shown on a single listing line.
+const DATA_PER_LINE: usize = 8;
+
+/// A parameter of a disassembled instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operand {
+    pub mode: ParamMode,
+    pub value: i64,
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode {
+            ParamMode::Position => write!(f, "[{}]", self.value),
+            ParamMode::Immediate => write!(f, "#{}", self.value),
+            ParamMode::Relative if self.value < 0 => write!(f, "rb{}", self.value),
+            ParamMode::Relative => write!(f, "rb+{}", self.value),
+        }
+    }
+}
+
+/// One line of a listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    Instruction {
+        addr: usize,
+        opcode: Opcode,
+        operands: Vec<Operand>,
+    },
+    /// Cells that are never reached as code, e.g. variables and constants, or instructions with
+    /// stray mode digits that a mnemonic can't express.
+    Data { addr: usize, values: Vec<i64> },
+}
+
+impl Item {
+    pub fn addr(&self) -> usize {
+        match self {
+            Item::Instruction { addr, .. } | Item::Data { addr, .. } => *addr,
+        }
+    }
+
+    /// The number of memory cells the item covers.
+    pub fn size(&self) -> usize {
+        match self {
+            Item::Instruction { operands, .. } => 1 + operands.len(),
+            Item::Data { values, .. } => values.len(),
+        }
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}: ", self.addr())?;
+
+        match self {
+            Item::Instruction {
+                opcode, operands, ..
+            } => {
+                write!(f, "{}", opcode.mnemonic())?;
+                for (i, operand) in operands.iter().enumerate() {
+                    if i == 0 {
+                        // Line the first operand up across mnemonics of different lengths.
+                        write!(f, "{:width$}", "", width = 5 - opcode.mnemonic().len())?;
+                    } else {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{operand}")?;
+                }
+            }
+            Item::Data { values, .. } => {
+                write!(f, ".data")?;
+                for (i, value) in values.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { ", " };
+                    write!(f, "{separator}{value}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the addresses of every instruction reachable from the start of the program.
+///
+/// Control flow is followed from address 0 through fall-through and jumps whose target is an
+/// immediate value. Jumps through memory can't be followed statically, so code only reached that
+/// way is treated as data.
+pub fn reachable_instructions(program: &[i64]) -> BTreeSet<usize> {
+    let mut covered = vec![false; program.len()];
+    let mut starts = BTreeSet::new();
+    let mut pending = vec![0];
+
+    while let Some(addr) = pending.pop() {
+        if addr >= program.len() || covered[addr] {
+            continue;
+        }
+
+        let Ok(ins) = Instruction::decode(addr, program[addr]) else {
+            continue;
+        };
+        let end = addr + ins.size();
+        if end > program.len() || covered[addr..end].iter().any(|c| *c) {
+            continue;
+        }
+
+        covered[addr..end].iter_mut().for_each(|c| *c = true);
+        starts.insert(addr);
+        pending.extend(successors(program, addr, &ins));
+    }
+
+    starts
+}
+
+/// The addresses control may move to after executing `ins` at `addr`, where known statically.
+pub(crate) fn successors(program: &[i64], addr: usize, ins: &Instruction) -> Vec<usize> {
+    let next = addr + ins.size();
+
+    match ins.opcode {
+        Opcode::Halt => vec![],
+        Opcode::JumpIfTrue | Opcode::JumpIfFalse => {
+            let condition = match ins.mode(1) {
+                ParamMode::Immediate => Some(program[addr + 1] != 0),
+                _ => None,
+            };
+            let target = match ins.mode(2) {
+                ParamMode::Immediate => usize::try_from(program[addr + 2]).ok(),
+                _ => None,
+            };
+            let jumps = condition.map(|c| c == (ins.opcode == Opcode::JumpIfTrue));
+
+            match jumps {
+                Some(true) => target.into_iter().collect(),
+                Some(false) => vec![next],
+                None => target.into_iter().chain([next]).collect(),
+            }
+        }
+        _ => vec![next],
+    }
+}
+
+/// Disassemble the single instruction at `addr`, whose raw value is `cells[0]` and whose
+/// parameters follow it. Returns `None` if the cells don't hold a valid instruction.
+pub fn instruction_at(cells: &[i64], addr: usize) -> Option<Item> {
+    let ins = Instruction::decode(addr, *cells.first()?).ok()?;
+    let operands = (1..ins.size())
+        .map(|n| {
+            Some(Operand {
+                mode: ins.mode(n),
+                value: *cells.get(n)?,
+            })
+        })
+        .collect::<Option<_>>()?;
+
+    Some(Item::Instruction {
+        addr,
+        opcode: ins.opcode,
+        operands,
+    })
+}
+
+/// Disassemble a program into listing items, in address order.
+///
+/// A reachable instruction is only listed as one if encoding it again gives back its raw value.
+/// Mode digits for parameters the opcode doesn't have (`1104` for `out #x`, or `199`) are ignored
+/// when it runs but would be lost by assembling its mnemonic, so it's listed as data instead.
+pub fn disassemble(program: &[i64]) -> Vec<Item> {
+    let starts = reachable_instructions(program);
+    let is_code = |addr: usize| {
+        starts.contains(&addr)
+            && Instruction::decode(addr, program[addr])
+                .is_ok_and(|ins| ins.encode() == program[addr])
+    };
+    let mut items = vec![];
+    let mut addr = 0;
+
+    while addr < program.len() {
+        if is_code(addr) {
+            let ins = Instruction::decode(addr, program[addr]).unwrap();
+            items.push(instruction_at(&program[addr..], addr).unwrap());
+            addr += ins.size();
+        } else {
+            let end = (addr..program.len())
+                .take(DATA_PER_LINE)
+                .find(|a| is_code(*a))
+                .unwrap_or_else(|| program.len().min(addr + DATA_PER_LINE));
+
+            items.push(Item::Data {
+                addr,
+                values: program[addr..end].to_vec(),
+            });
+            addr = end;
+        }
+    }
+
+    items
+}
+
+/// Disassemble a program into a printable listing, one item per line.
+pub fn listing(program: &[i64]) -> String {
+    disassemble(program)
+        .iter()
+        .map(|item| format!("{item}\n"))
+        .collect()
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+
+/// Reasons an Intcode program can fail. Every variant records the instruction pointer and the
+/// raw (undecoded) instruction that was executing when the failure happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntcodeError {
+    /// The instruction's opcode isn't one the machine knows.
+    InvalidOpcode { ip: usize, instruction: i64 },
+    /// Parameter `param` (1-based) has a mode other than position, immediate or relative.
+    InvalidMode {
+        ip: usize,
+        instruction: i64,
+        param: u32,
+    },
+    /// A parameter, relative base offset or jump resolved to a negative address.
+    NegativeAddress {
+        ip: usize,
+        instruction: i64,
+        address: i64,
+    },
+    /// Parameter `param` is written to by the instruction but is in immediate mode.
+    ImmediateWrite {
+        ip: usize,
+        instruction: i64,
+        param: u32,
+    },
+    /// The program asked for input but none was available.
+    InputExhausted { ip: usize, instruction: i64 },
+    /// The machine used up its instruction budget before this instruction could execute.
+    BudgetExhausted { ip: usize, instruction: i64 },
+    /// An addition, multiplication or relative base adjustment overflowed an `i64`.
+    Overflow { ip: usize, instruction: i64 },
+}
+
+impl IntcodeError {
+    /// The instruction pointer of the failing instruction.
+    pub fn ip(&self) -> usize {
+        match *self {
+            IntcodeError::InvalidOpcode { ip, .. }
+            | IntcodeError::InvalidMode { ip, .. }
+            | IntcodeError::NegativeAddress { ip, .. }
+            | IntcodeError::ImmediateWrite { ip, .. }
+            | IntcodeError::InputExhausted { ip, .. }
+            | IntcodeError::BudgetExhausted { ip, .. }
+            | IntcodeError::Overflow { ip, .. } => ip,
+        }
+    }
+
+    /// The raw value of the failing instruction.
+    pub fn instruction(&self) -> i64 {
+        match *self {
+            IntcodeError::InvalidOpcode { instruction, .. }
+            | IntcodeError::InvalidMode { instruction, .. }
+            | IntcodeError::NegativeAddress { instruction, .. }
+            | IntcodeError::ImmediateWrite { instruction, .. }
+            | IntcodeError::InputExhausted { instruction, .. }
+            | IntcodeError::BudgetExhausted { instruction, .. }
+            | IntcodeError::Overflow { instruction, .. } => instruction,
+        }
+    }
+}
+
+impl fmt::Display for IntcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: ", self.ip(), self.instruction())?;
+
+        match self {
+            IntcodeError::InvalidOpcode { instruction, .. } => {
+                write!(f, "invalid opcode {}", instruction % 100)
+            }
+            IntcodeError::InvalidMode { param, .. } => {
+                write!(f, "unknown mode for parameter {param}")
+            }
+            IntcodeError::NegativeAddress { address, .. } => {
+                write!(f, "negative address {address}")
+            }
+            IntcodeError::ImmediateWrite { param, .. } => {
+                write!(
+                    f,
+                    "parameter {param} is written to but is in immediate mode"
+                )
+            }
+            IntcodeError::InputExhausted { .. } => write!(f, "no input available"),
+            IntcodeError::BudgetExhausted { .. } => write!(f, "instruction budget exhausted"),
+            IntcodeError::Overflow { .. } => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+impl std::error::Error for IntcodeError {}
@@ -0,0 +1,174 @@
+use super::IntcodeError;
+
+/// How an instruction parameter is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParamMode {
+    /// The parameter is the address of the value.
+    Position,
+    /// The parameter is the value itself.
+    Immediate,
+    /// The parameter is an offset from the relative base to the address of the value.
+    Relative,
+}
+
+impl ParamMode {
+    fn parse(n: i64) -> Option<Self> {
+        match n {
+            0 => Some(Self::Position),
+            1 => Some(Self::Immediate),
+            2 => Some(Self::Relative),
+            _ => None,
+        }
+    }
+
+    /// The digit that selects this mode in an encoded instruction.
+    pub fn code(self) -> i64 {
+        match self {
+            ParamMode::Position => 0,
+            ParamMode::Immediate => 1,
+            ParamMode::Relative => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Add,
+    Multiply,
+    Input,
+    Output,
+    JumpIfTrue,
+    JumpIfFalse,
+    LessThan,
+    Equals,
+    AdjustRelativeBase,
+    Halt,
+}
+
+impl Opcode {
+    pub const ALL: [Opcode; 10] = [
+        Opcode::Add,
+        Opcode::Multiply,
+        Opcode::Input,
+        Opcode::Output,
+        Opcode::JumpIfTrue,
+        Opcode::JumpIfFalse,
+        Opcode::LessThan,
+        Opcode::Equals,
+        Opcode::AdjustRelativeBase,
+        Opcode::Halt,
+    ];
+
+    pub fn parse(n: i64) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.code() == n)
+    }
+
+    /// The two low digits that select this opcode in an encoded instruction.
+    pub fn code(self) -> i64 {
+        match self {
+            Opcode::Add => 1,
+            Opcode::Multiply => 2,
+            Opcode::Input => 3,
+            Opcode::Output => 4,
+            Opcode::JumpIfTrue => 5,
+            Opcode::JumpIfFalse => 6,
+            Opcode::LessThan => 7,
+            Opcode::Equals => 8,
+            Opcode::AdjustRelativeBase => 9,
+            Opcode::Halt => 99,
+        }
+    }
+
+    /// The short name used in assembly listings.
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Add => "add",
+            Opcode::Multiply => "mul",
+            Opcode::Input => "in",
+            Opcode::Output => "out",
+            Opcode::JumpIfTrue => "jnz",
+            Opcode::JumpIfFalse => "jz",
+            Opcode::LessThan => "lt",
+            Opcode::Equals => "eq",
+            Opcode::AdjustRelativeBase => "arb",
+            Opcode::Halt => "hlt",
+        }
+    }
+
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.mnemonic() == mnemonic)
+    }
+
+    /// The number of parameters following the instruction.
+    pub fn param_count(self) -> usize {
+        match self {
+            Opcode::Add | Opcode::Multiply | Opcode::LessThan | Opcode::Equals => 3,
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => 2,
+            Opcode::Input | Opcode::Output | Opcode::AdjustRelativeBase => 1,
+            Opcode::Halt => 0,
+        }
+    }
+
+    /// The parameter (1-based) the instruction writes its result to, if it writes one.
+    pub fn write_param(self) -> Option<usize> {
+        match self {
+            Opcode::Add | Opcode::Multiply | Opcode::LessThan | Opcode::Equals => Some(3),
+            Opcode::Input => Some(1),
+            _ => None,
+        }
+    }
+}
+
+/// A decoded instruction: the opcode and the mode of each parameter it takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub modes: [ParamMode; 3],
+}
+
+impl Instruction {
+    /// Decode the raw instruction found at `ip`.
+    ///
+    /// Only the modes of parameters the opcode takes are validated. Writing to an immediate mode
+    /// parameter isn't rejected here, since that only fails when the instruction executes.
+    pub fn decode(ip: usize, raw: i64) -> Result<Self, IntcodeError> {
+        let opcode = Opcode::parse(raw % 100).ok_or(IntcodeError::InvalidOpcode {
+            ip,
+            instruction: raw,
+        })?;
+
+        let mut modes = [ParamMode::Position; 3];
+        for (n, mode) in modes.iter_mut().enumerate().take(opcode.param_count()) {
+            *mode = ParamMode::parse(raw / 10_i64.pow(n as u32 + 2) % 10).ok_or(
+                IntcodeError::InvalidMode {
+                    ip,
+                    instruction: raw,
+                    param: n as u32 + 1,
+                },
+            )?;
+        }
+
+        Ok(Instruction { opcode, modes })
+    }
+
+    /// Encode the instruction back into its raw form.
+    pub fn encode(&self) -> i64 {
+        self.modes
+            .iter()
+            .take(self.opcode.param_count())
+            .enumerate()
+            .fold(self.opcode.code(), |raw, (n, mode)| {
+                raw + mode.code() * 10_i64.pow(n as u32 + 2)
+            })
+    }
+
+    /// The number of memory cells the instruction occupies, including its parameters.
+    pub fn size(&self) -> usize {
+        1 + self.opcode.param_count()
+    }
+
+    /// The mode of parameter `n` (1-based).
+    pub fn mode(&self, n: usize) -> ParamMode {
+        self.modes[n - 1]
+    }
+}
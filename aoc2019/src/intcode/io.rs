@@ -0,0 +1,143 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::{Receiver, Sender};
+
+/// A source of values for a program's input instructions.
+pub trait IntcodeInput {
+    /// Produce the next input value, or `None` if none is available. A machine with no input
+    /// available stops with [`Event::NeedsInput`](super::Event::NeedsInput).
+    fn read(&mut self) -> Option<i64>;
+}
+
+/// A destination for the values a program outputs.
+pub trait IntcodeOutput {
+    fn write(&mut self, value: i64);
+}
+
+impl IntcodeInput for VecDeque<i64> {
+    fn read(&mut self) -> Option<i64> {
+        self.pop_front()
+    }
+}
+
+/// Blocks until a value is received, so a machine reading from a channel only needs input once
+/// every sender is gone.
+impl IntcodeInput for Receiver<i64> {
+    fn read(&mut self) -> Option<i64> {
+        self.recv().ok()
+    }
+}
+
+impl<F: FnMut() -> Option<i64>> IntcodeInput for F {
+    fn read(&mut self) -> Option<i64> {
+        self()
+    }
+}
+
+/// Input taken from any iterator of values.
+#[derive(Debug, Clone)]
+pub struct IterInput<I>(pub I);
+
+impl<I: Iterator<Item = i64>> IntcodeInput for IterInput<I> {
+    fn read(&mut self) -> Option<i64> {
+        self.0.next()
+    }
+}
+
+impl IntcodeOutput for Vec<i64> {
+    fn write(&mut self, value: i64) {
+        self.push(value);
+    }
+}
+
+impl IntcodeOutput for VecDeque<i64> {
+    fn write(&mut self, value: i64) {
+        self.push_back(value);
+    }
+}
+
+/// Values sent after the receiver hangs up are dropped.
+impl IntcodeOutput for Sender<i64> {
+    fn write(&mut self, value: i64) {
+        let _ = self.send(value);
+    }
+}
+
+impl<F: FnMut(i64)> IntcodeOutput for F {
+    fn write(&mut self, value: i64) {
+        self(value)
+    }
+}
+
+/// Text input for programs that read ASCII characters.
+#[derive(Debug, Clone, Default)]
+pub struct AsciiInput(VecDeque<i64>);
+
+impl AsciiInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the characters of `text`.
+    pub fn push_str(&mut self, text: &str) {
+        self.0.extend(text.bytes().map(i64::from));
+    }
+
+    /// Queue the characters of `line` followed by a newline.
+    pub fn push_line(&mut self, line: &str) {
+        self.push_str(line);
+        self.0.push_back(i64::from(b'\n'));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl IntcodeInput for AsciiInput {
+    fn read(&mut self) -> Option<i64> {
+        self.0.pop_front()
+    }
+}
+
+/// Text output for programs that write ASCII characters. Values outside the ASCII range, which
+/// programs usually use for a final answer, are kept separately.
+#[derive(Debug, Clone, Default)]
+pub struct AsciiOutput {
+    text: String,
+    non_ascii: Vec<i64>,
+}
+
+impl AsciiOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The text output so far.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Take the text output so far, leaving the buffer empty.
+    pub fn take_text(&mut self) -> String {
+        std::mem::take(&mut self.text)
+    }
+
+    /// Values output that aren't ASCII characters, in the order they were output.
+    pub fn non_ascii(&self) -> &[i64] {
+        &self.non_ascii
+    }
+
+    /// Take the non-ASCII values output so far, leaving none.
+    pub fn take_non_ascii(&mut self) -> Vec<i64> {
+        std::mem::take(&mut self.non_ascii)
+    }
+}
+
+impl IntcodeOutput for AsciiOutput {
+    fn write(&mut self, value: i64) {
+        match u8::try_from(value) {
+            Ok(c) if c.is_ascii() => self.text.push(char::from(c)),
+            _ => self.non_ascii.push(value),
+        }
+    }
+}
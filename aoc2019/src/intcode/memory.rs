@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+/// How far past the end of the dense region a write may land before it is stored sparsely
+/// instead of growing the vector.
+const MAX_GROWTH: usize = 0x1000;
+
+/// Intcode memory.
+///
+/// Addresses up to a little past the highest one written so far are backed by a vector that grows
+/// on demand, while writes far beyond it go into a sparse map so that a single high address
+/// doesn't allocate everything below it. Cells that were never written read as 0.
+#[derive(Clone, Debug, Default)]
+pub struct Memory {
+    dense: Vec<i64>,
+    sparse: HashMap<usize, i64>,
+}
+
+impl Memory {
+    pub fn new(program: &[i64]) -> Self {
+        Memory {
+            dense: program.to_vec(),
+            sparse: HashMap::new(),
+        }
+    }
+
+    pub fn read(&self, addr: usize) -> i64 {
+        match self.dense.get(addr) {
+            Some(value) => *value,
+            None => self.sparse.get(&addr).copied().unwrap_or(0),
+        }
+    }
+
+    pub fn write(&mut self, addr: usize, value: i64) {
+        if addr >= self.dense.len() {
+            if addr - self.dense.len() >= MAX_GROWTH {
+                self.sparse.insert(addr, value);
+                return;
+            }
+
+            self.grow(addr + 1);
+        }
+
+        self.dense[addr] = value;
+    }
+
+    /// The contiguous cells starting at address 0.
+    pub fn dense(&self) -> &[i64] {
+        &self.dense
+    }
+
+    /// The cells stored outside the dense region, in no particular order.
+    pub fn sparse(&self) -> impl Iterator<Item = (usize, i64)> + '_ {
+        self.sparse.iter().map(|(addr, value)| (*addr, *value))
+    }
+
+    /// Extend the dense region to `len` cells, pulling in any sparse cells it now covers.
+    fn grow(&mut self, len: usize) {
+        let start = self.dense.len();
+        self.dense.resize(len, 0);
+
+        if !self.sparse.is_empty() {
+            let dense = &mut self.dense;
+            self.sparse.retain(|&addr, value| {
+                if (start..len).contains(&addr) {
+                    dense[addr] = *value;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+}
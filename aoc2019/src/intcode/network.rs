@@ -0,0 +1,340 @@
+//! Run several Intcode machines together on one thread, routing each machine's output to the
+//! input of others.
+
+use super::{Event, IntcodeComputer, IntcodeError};
+use std::fmt;
+
+/// How the machines in a [`Network`] are connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Each machine's output is the next machine's input. The last machine's output leaves the
+    /// network.
+    Chain,
+    /// Like a chain, but the last machine's output also loops back into the first machine.
+    Ring,
+    /// Machines send each other packets of three values: a destination address, X and Y. Every
+    /// machine is given its address as its first input, and reads -1 whenever it has no packets
+    /// waiting.
+    ///
+    /// Packets sent to `nat` are held by the NAT, which remembers only the last one. When every
+    /// machine has no packets waiting and has asked for input again after being given -1, the
+    /// network is idle and the NAT sends its packet to machine 0. Packets sent to any other
+    /// address without a machine are dropped.
+    Packets { nat: i64 },
+}
+
+/// The X and Y values of a packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packet {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// Something that happened in the network which the caller may need to act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkEvent {
+    /// The last machine of a chain or ring output a value.
+    Output(i64),
+    /// A machine sent a packet to the NAT.
+    NatReceived(Packet),
+    /// The network was idle, so the NAT sent its packet to machine 0.
+    NatSent(Packet),
+    /// Every machine has halted.
+    Halted,
+    /// No machine can make progress: every machine that hasn't halted is waiting for input that
+    /// nothing will send, or a packet network is idle with nothing for the NAT to send.
+    Deadlocked,
+}
+
+/// Reasons a network can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkError {
+    /// Machine `machine` failed, including by running out of its instruction budget.
+    Machine { machine: usize, error: IntcodeError },
+    /// Every machine that hasn't halted is waiting for input that nothing will send. `blocked`
+    /// lists each of them with the address of the input instruction it's stuck on.
+    Deadlock { blocked: Vec<(usize, usize)> },
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkError::Machine { machine, error } => write!(f, "machine {machine}: {error}"),
+            NetworkError::Deadlock { blocked } => {
+                let blocked: Vec<String> = blocked
+                    .iter()
+                    .map(|(machine, ip)| format!("machine {machine} at [{ip}]"))
+                    .collect();
+                write!(
+                    f,
+                    "deadlock: machines {} are waiting for input",
+                    blocked.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ready,
+    Waiting,
+    Halted,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    computer: IntcodeComputer,
+    state: State,
+    idle: bool,       // asked for input again after being given -1, in a packet network
+    packet: Vec<i64>, // values of a packet the machine is part way through sending
+}
+
+impl Node {
+    fn waiting_on_empty_queue(&self) -> bool {
+        self.state == State::Waiting && self.computer.input().is_empty()
+    }
+}
+
+/// A group of machines run cooperatively: the scheduler runs one machine until it needs input
+/// or halts, then moves on to the next machine that can make progress.
+#[derive(Debug, Clone)]
+pub struct Network {
+    nodes: Vec<Node>,
+    topology: Topology,
+    current: usize, // where the scheduler looks for the next machine to run
+    output: Vec<i64>,
+    nat: Option<Packet>,
+}
+
+impl Network {
+    /// Create a network of `count` machines all running `program`.
+    pub fn new(program: &[i64], count: usize, topology: Topology) -> Self {
+        Self::from_machines(
+            (0..count).map(|_| IntcodeComputer::new(program)).collect(),
+            topology,
+        )
+    }
+
+    /// Create a network from machines that have already been set up, e.g. patched or restored
+    /// from snapshots.
+    pub fn from_machines(machines: Vec<IntcodeComputer>, topology: Topology) -> Self {
+        let mut nodes: Vec<Node> = machines
+            .into_iter()
+            .map(|computer| Node {
+                computer,
+                state: State::Ready,
+                idle: false,
+                packet: vec![],
+            })
+            .collect();
+
+        if let Topology::Packets { .. } = topology {
+            for (addr, node) in nodes.iter_mut().enumerate() {
+                node.computer.push_input(addr as i64);
+            }
+        }
+
+        Network {
+            nodes,
+            topology,
+            current: 0,
+            output: vec![],
+            nat: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn machine(&self, n: usize) -> &IntcodeComputer {
+        &self.nodes[n].computer
+    }
+
+    /// Limit every machine to executing `budget` more instructions, or lift the limit with `None`.
+    /// A machine that uses up its budget fails the network with
+    /// [`IntcodeError::BudgetExhausted`].
+    pub fn set_budget(&mut self, budget: Option<u64>) {
+        for node in &mut self.nodes {
+            node.computer.set_budget(budget);
+        }
+    }
+
+    /// Queue a value for machine `n`'s next input instruction.
+    pub fn push_input(&mut self, n: usize, value: i64) {
+        self.nodes[n].computer.push_input(value);
+    }
+
+    /// The values that have left the end of a chain or ring.
+    pub fn output(&self) -> &[i64] {
+        &self.output
+    }
+
+    /// The last packet the NAT received.
+    pub fn nat(&self) -> Option<Packet> {
+        self.nat
+    }
+
+    /// The machines waiting for input with none queued.
+    pub fn blocked(&self) -> impl Iterator<Item = usize> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.waiting_on_empty_queue())
+            .map(|(n, _)| n)
+    }
+
+    fn runnable(&self, n: usize) -> bool {
+        let node = &self.nodes[n];
+
+        match node.state {
+            State::Ready => true,
+            State::Halted => false,
+            // Machines in a packet network are given -1 when there's nothing for them.
+            State::Waiting => {
+                matches!(self.topology, Topology::Packets { .. }) || !node.waiting_on_empty_queue()
+            }
+        }
+    }
+
+    /// Find the next machine to run, starting from the current one.
+    fn next_runnable(&self) -> Option<usize> {
+        (0..self.nodes.len())
+            .map(|k| (self.current + k) % self.nodes.len())
+            .find(|&n| self.runnable(n))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.nodes
+            .iter()
+            .all(|node| node.state == State::Halted || (node.idle && node.waiting_on_empty_queue()))
+    }
+
+    /// Deliver a value output by machine `from`, returning the event it raises, if any.
+    fn route(&mut self, from: usize, value: i64) -> Option<NetworkEvent> {
+        let last = from + 1 == self.nodes.len();
+
+        match self.topology {
+            Topology::Chain | Topology::Ring if !last => {
+                self.nodes[from + 1].computer.push_input(value);
+                None
+            }
+            Topology::Chain | Topology::Ring => {
+                if self.topology == Topology::Ring {
+                    self.nodes[0].computer.push_input(value);
+                }
+                self.output.push(value);
+                Some(NetworkEvent::Output(value))
+            }
+            Topology::Packets { nat } => {
+                let node = &mut self.nodes[from];
+                node.idle = false;
+                node.packet.push(value);
+                if node.packet.len() < 3 {
+                    return None;
+                }
+
+                let (to, packet) = match node.packet.drain(..).as_slice() {
+                    &[to, x, y] => (to, Packet { x, y }),
+                    _ => unreachable!(),
+                };
+
+                if to == nat {
+                    self.nat = Some(packet);
+                    return Some(NetworkEvent::NatReceived(packet));
+                }
+
+                if let Some(node) = usize::try_from(to)
+                    .ok()
+                    .and_then(|to| self.nodes.get_mut(to))
+                {
+                    node.computer.push_input(packet.x);
+                    node.computer.push_input(packet.y);
+                    node.idle = false;
+                }
+                None
+            }
+        }
+    }
+
+    /// Run machines until something happens in the network.
+    pub fn run_until_event(&mut self) -> Result<NetworkEvent, NetworkError> {
+        loop {
+            let Some(n) = self.next_runnable() else {
+                if self.nodes.iter().all(|node| node.state == State::Halted) {
+                    return Ok(NetworkEvent::Halted);
+                }
+                return Ok(NetworkEvent::Deadlocked);
+            };
+            self.current = n;
+
+            if self.nodes[n].waiting_on_empty_queue() {
+                // Only packet networks run machines with nothing to read.
+                if self.is_idle() {
+                    let Some(packet) = self.nat else {
+                        return Ok(NetworkEvent::Deadlocked);
+                    };
+
+                    let first = &mut self.nodes[0];
+                    first.computer.push_input(packet.x);
+                    first.computer.push_input(packet.y);
+                    first.idle = false;
+                    return Ok(NetworkEvent::NatSent(packet));
+                }
+
+                self.nodes[n].computer.push_input(-1);
+                self.nodes[n].idle = true;
+            }
+
+            let node = &mut self.nodes[n];
+            let event = node
+                .computer
+                .run_until_event()
+                .map_err(|error| NetworkError::Machine { machine: n, error })?;
+
+            match event {
+                Event::Output(value) => {
+                    node.state = State::Ready;
+                    node.computer.output_mut().clear();
+                    if let Some(event) = self.route(n, value) {
+                        return Ok(event);
+                    }
+                }
+                Event::NeedsInput => {
+                    node.state = State::Waiting;
+                    self.current = (n + 1) % self.nodes.len();
+                }
+                Event::Halted => {
+                    node.state = State::Halted;
+                    self.current = (n + 1) % self.nodes.len();
+                }
+            }
+        }
+    }
+
+    /// Run until every machine halts, skipping other events. A deadlock is an error describing
+    /// where the machines are stuck, rather than something that hangs.
+    pub fn run(&mut self) -> Result<(), NetworkError> {
+        loop {
+            match self.run_until_event()? {
+                NetworkEvent::Halted => return Ok(()),
+                NetworkEvent::Deadlocked => {
+                    return Err(NetworkError::Deadlock {
+                        blocked: self
+                            .blocked()
+                            .map(|n| (n, self.nodes[n].computer.ip()))
+                            .collect(),
+                    })
+                }
+                _ => {}
+            }
+        }
+    }
+}
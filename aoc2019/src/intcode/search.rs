@@ -0,0 +1,166 @@
+//! Brute-force searches over many candidate inputs, spread across worker threads.
+//!
+//! Results never depend on how the threads happen to be scheduled: [`Search::first`] finds the
+//! earliest matching candidate in the order given and [`Search::best`] breaks ties in favour of
+//! the earliest candidate, exactly as a sequential search would.
+
+use super::IntcodeComputer;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// A pool of worker threads to search with.
+#[derive(Debug, Clone, Copy)]
+pub struct Search {
+    workers: usize,
+}
+
+impl Default for Search {
+    /// One worker per available CPU.
+    fn default() -> Self {
+        Search {
+            workers: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `workers` threads instead of one per CPU.
+    pub fn workers(self, workers: usize) -> Self {
+        Search {
+            workers: workers.max(1),
+        }
+    }
+
+    /// Evaluate `eval` for candidate indices `0..len` across the workers, collecting every result
+    /// it returns, in index order. With `first_only`, stops once a result is found, though every
+    /// index before it is still evaluated.
+    fn evaluate<T: Send>(
+        &self,
+        len: usize,
+        first_only: bool,
+        eval: impl Fn(usize) -> Option<T> + Sync,
+    ) -> Vec<(usize, T)> {
+        let next = AtomicUsize::new(0);
+        let found = AtomicUsize::new(usize::MAX); // lowest index with a result so far
+        let results = Mutex::new(vec![]);
+
+        thread::scope(|scope| {
+            for _ in 0..self.workers.min(len) {
+                scope.spawn(|| {
+                    let mut local = vec![];
+
+                    // Indices are handed out in order, so every index below `found` is already
+                    // claimed by the time a worker sees it.
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= len || (first_only && i > found.load(Ordering::Relaxed)) {
+                            break;
+                        }
+
+                        if let Some(result) = eval(i) {
+                            found.fetch_min(i, Ordering::Relaxed);
+                            local.push((i, result));
+                        }
+                    }
+
+                    results.lock().unwrap().append(&mut local);
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_unstable_by_key(|(i, _)| *i);
+        results
+    }
+
+    /// The first candidate, in the order given, that `f` returns a result for, along with that
+    /// result.
+    pub fn first<'c, C: Sync, T: Send>(
+        &self,
+        candidates: &'c [C],
+        f: impl Fn(&C) -> Option<T> + Sync,
+    ) -> Option<(&'c C, T)> {
+        let results = self.evaluate(candidates.len(), true, |i| f(&candidates[i]));
+        let (i, result) = results.into_iter().next()?;
+        Some((&candidates[i], result))
+    }
+
+    /// The candidate that `f` gives the greatest result for, along with that result, or `None` if
+    /// there are no candidates. If `f` fails for any candidate, the error for the earliest one is
+    /// returned instead.
+    pub fn best<'c, C: Sync, T: Ord + Send, E: Send>(
+        &self,
+        candidates: &'c [C],
+        f: impl Fn(&C) -> Result<T, E> + Sync,
+    ) -> Result<Option<(&'c C, T)>, E> {
+        let results = self.evaluate(candidates.len(), false, |i| Some(f(&candidates[i])));
+
+        let mut best: Option<(usize, T)> = None;
+        for (i, result) in results {
+            let result = result?;
+            if best.as_ref().is_none_or(|(_, value)| result > *value) {
+                best = Some((i, result));
+            }
+        }
+
+        Ok(best.map(|(i, value)| (&candidates[i], value)))
+    }
+
+    /// Find the first set of patches which, written into a copy of `base` before running it to
+    /// completion, leaves the machine in a state `check` accepts. Candidates whose run fails are
+    /// skipped.
+    ///
+    /// A patch can turn the program into one that never halts, which would hang the search. Give
+    /// `base` a budget with [`IntcodeComputer::set_budget`] so every copy starts with it, and a
+    /// candidate that runs out is skipped like any other failure.
+    pub fn first_patched<'c, P, T>(
+        &self,
+        base: &IntcodeComputer,
+        patches: &'c [P],
+        check: impl Fn(&IntcodeComputer) -> Option<T> + Sync,
+    ) -> Option<(&'c P, T)>
+    where
+        P: AsRef<[(usize, i64)]> + Sync,
+        T: Send,
+    {
+        self.first(patches, |patches| {
+            let mut computer = base.clone();
+            for &(addr, value) in patches.as_ref() {
+                computer.write(addr, value);
+            }
+            computer.run().ok()?;
+            check(&computer)
+        })
+    }
+
+    /// Find the first input sequence which, queued into a copy of `base` before running it to
+    /// completion, leaves the machine in a state `check` accepts. Candidates whose run fails,
+    /// including by running out of input, are skipped.
+    ///
+    /// As with [`Search::first_patched`], set a budget on `base` if some inputs might make the
+    /// program loop forever.
+    pub fn first_with_inputs<'c, S, T>(
+        &self,
+        base: &IntcodeComputer,
+        inputs: &'c [S],
+        check: impl Fn(&IntcodeComputer) -> Option<T> + Sync,
+    ) -> Option<(&'c S, T)>
+    where
+        S: AsRef<[i64]> + Sync,
+        T: Send,
+    {
+        self.first(inputs, |inputs| {
+            let mut computer = base.clone();
+            for &value in inputs.as_ref() {
+                computer.push_input(value);
+            }
+            computer.run().ok()?;
+            check(&computer)
+        })
+    }
+}
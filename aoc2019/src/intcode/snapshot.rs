@@ -0,0 +1,173 @@
+use super::cache::DecodeCache;
+use super::{IntcodeComputer, Memory};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+/// The complete state of a machine at one point in its execution: memory, registers, and any
+/// input not yet consumed and output already produced.
+///
+/// Restoring a snapshot rewinds the machine to exactly that point, so searches can fork a machine
+/// part way through a program instead of replaying it from the start.
+#[derive(Debug, Clone)]
+pub struct Snapshot<I = VecDeque<i64>, O = Vec<i64>> {
+    mem: Memory,
+    ip: usize,
+    relative_base: i64,
+    input: I,
+    output: O,
+}
+
+impl<I: Clone, O: Clone> IntcodeComputer<I, O> {
+    /// Capture the machine's current state.
+    pub fn snapshot(&self) -> Snapshot<I, O> {
+        Snapshot {
+            mem: self.mem.clone(),
+            ip: self.ip,
+            relative_base: self.relative_base,
+            input: self.input.clone(),
+            output: self.output.clone(),
+        }
+    }
+
+    /// Put the machine back into the state captured by `snapshot`. The machine's instruction
+    /// budget isn't part of its state and is left as it is.
+    pub fn restore(&mut self, snapshot: &Snapshot<I, O>) {
+        self.mem.clone_from(&snapshot.mem);
+        self.ip = snapshot.ip;
+        self.relative_base = snapshot.relative_base;
+        self.input.clone_from(&snapshot.input);
+        self.output.clone_from(&snapshot.output);
+
+        // The cached instructions were decoded from the memory just replaced.
+        if let Some(cache) = &mut self.cache {
+            *cache = DecodeCache::default();
+        }
+    }
+}
+
+impl<I, O> From<Snapshot<I, O>> for IntcodeComputer<I, O> {
+    fn from(snapshot: Snapshot<I, O>) -> Self {
+        IntcodeComputer {
+            mem: snapshot.mem,
+            ip: snapshot.ip,
+            relative_base: snapshot.relative_base,
+            input: snapshot.input,
+            output: snapshot.output,
+            budget: None,
+            cache: None,
+        }
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Parse the value of a `key value` line.
+fn parse_value<T: FromStr>(key: &str, value: &str) -> io::Result<T> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| invalid_data(format!("invalid `{key}` value `{value}`")))
+}
+
+/// Parse an address. A running program can only reach addresses that fit in an `i64`, so larger
+/// ones are rejected along with negative ones.
+fn parse_address(s: &str) -> Option<usize> {
+    usize::try_from(s.trim().parse::<i64>().ok()?).ok()
+}
+
+fn parse_values(s: &str) -> io::Result<Vec<i64>> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            v.parse()
+                .map_err(|_| invalid_data(format!("invalid value `{v}`")))
+        })
+        .collect()
+}
+
+fn join_values<'a>(values: impl IntoIterator<Item = &'a i64>) -> String {
+    values
+        .into_iter()
+        .map(i64::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Snapshots of machines with the default queue input and `Vec` output can be saved as text, one
+/// `key value` pair per line:
+///
+/// ```text
+/// ip 25
+/// rb 1000
+/// input 5,6
+/// output 1,2
+/// mem 1102,34463338,34463338,63
+/// cell 100000 7
+/// ```
+///
+/// `mem` holds the memory from address 0 as a comma-separated program and each `cell` line a
+/// single cell beyond it.
+impl Snapshot {
+    pub fn write_to<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "ip {}", self.ip)?;
+        writeln!(w, "rb {}", self.relative_base)?;
+        writeln!(w, "input {}", join_values(&self.input))?;
+        writeln!(w, "output {}", join_values(&self.output))?;
+        writeln!(w, "mem {}", join_values(self.mem.dense()))?;
+
+        let mut sparse: Vec<(usize, i64)> = self.mem.sparse().collect();
+        sparse.sort_unstable();
+        for (addr, value) in sparse {
+            writeln!(w, "cell {addr} {value}")?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_from<R: BufRead>(r: R) -> io::Result<Self> {
+        let mut snapshot = Snapshot {
+            mem: Memory::default(),
+            ip: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+            output: vec![],
+        };
+        let mut cells = vec![];
+
+        for line in r.lines() {
+            let line = line?;
+            let (key, value) = line.split_once(' ').unwrap_or((&line, ""));
+            match key {
+                "ip" => {
+                    snapshot.ip = parse_address(value)
+                        .ok_or_else(|| invalid_data(format!("invalid `ip` value `{value}`")))?
+                }
+                "rb" => snapshot.relative_base = parse_value(key, value)?,
+                "input" => snapshot.input = parse_values(value)?.into(),
+                "output" => snapshot.output = parse_values(value)?,
+                "mem" => snapshot.mem = Memory::new(&parse_values(value)?),
+                "cell" => {
+                    let (addr, value) = value
+                        .trim()
+                        .split_once(' ')
+                        .and_then(|(a, v)| Some((parse_address(a)?, v.parse().ok()?)))
+                        .ok_or_else(|| invalid_data(format!("invalid cell `{value}`")))?;
+                    cells.push((addr, value));
+                }
+                "" => {}
+                _ => return Err(invalid_data(format!("unknown key `{key}`"))),
+            }
+        }
+
+        // Cells are written after the dense memory, which may come later in the file.
+        for (addr, value) in cells {
+            snapshot.mem.write(addr, value);
+        }
+
+        Ok(snapshot)
+    }
+}
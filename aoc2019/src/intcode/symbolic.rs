@@ -0,0 +1,477 @@
+//! Run a program with some memory cells left as unknowns, to see how its results depend on them.
+//!
+//! Values are tracked as polynomials over the named symbols, so adding and multiplying work as
+//! usual. Everything that decides where the program goes next (instructions, addresses, jumps,
+//! comparisons and the relative base) has to stay concrete; a program whose control flow depends
+//! on a symbol fails with [`SymbolicError::NotConcrete`].
+//!
+//! Arithmetic on concrete values fails with [`IntcodeError::Overflow`], as on the concrete
+//! machine. Whether arithmetic on symbols overflows depends on their values, so coefficients wrap
+//! instead, and overflow is only caught once [`Expr::eval`] has values to work with. A program
+//! that overflows part way through a calculation and then comes back into range isn't caught.
+
+use super::{Instruction, IntcodeError, Opcode, ParamMode};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// A polynomial with integer coefficients over named symbols.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expr {
+    // Each term's symbols, sorted and repeated for powers, mapped to its nonzero coefficient. The
+    // constant term has no symbols.
+    terms: BTreeMap<Vec<String>, i64>,
+}
+
+impl Expr {
+    pub fn constant(value: i64) -> Self {
+        let mut expr = Expr::default();
+        expr.add_term(vec![], value);
+        expr
+    }
+
+    pub fn symbol(name: &str) -> Self {
+        let mut expr = Expr::default();
+        expr.add_term(vec![name.to_string()], 1);
+        expr
+    }
+
+    fn add_term(&mut self, symbols: Vec<String>, coefficient: i64) {
+        let c = self
+            .terms
+            .get(&symbols)
+            .copied()
+            .unwrap_or(0)
+            .wrapping_add(coefficient);
+        if c == 0 {
+            self.terms.remove(&symbols);
+        } else {
+            self.terms.insert(symbols, c);
+        }
+    }
+
+    /// The value of the expression if it doesn't depend on any symbol.
+    pub fn as_constant(&self) -> Option<i64> {
+        match self.terms.iter().next() {
+            None => Some(0),
+            Some((symbols, c)) if symbols.is_empty() && self.terms.len() == 1 => Some(*c),
+            _ => None,
+        }
+    }
+
+    /// The symbols the expression depends on.
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut symbols: Vec<&str> = self.terms.keys().flatten().map(String::as_str).collect();
+        symbols.sort_unstable();
+        symbols.dedup();
+        symbols
+    }
+
+    pub fn add(&self, other: &Expr) -> Expr {
+        let mut sum = self.clone();
+        for (symbols, c) in &other.terms {
+            sum.add_term(symbols.clone(), *c);
+        }
+        sum
+    }
+
+    pub fn mul(&self, other: &Expr) -> Expr {
+        let mut product = Expr::default();
+        for (a_symbols, a) in &self.terms {
+            for (b_symbols, b) in &other.terms {
+                let mut symbols: Vec<String> = a_symbols.iter().chain(b_symbols).cloned().collect();
+                symbols.sort_unstable();
+                product.add_term(symbols, a.wrapping_mul(*b));
+            }
+        }
+        product
+    }
+
+    /// Evaluate the expression, looking up each symbol's value with `value`. Returns `None` if
+    /// the result overflows.
+    pub fn eval(&self, value: impl Fn(&str) -> i64) -> Option<i64> {
+        self.terms.iter().try_fold(0i64, |sum, (symbols, c)| {
+            let term = symbols
+                .iter()
+                .try_fold(*c, |product, s| product.checked_mul(value(s)))?;
+            sum.checked_add(term)
+        })
+    }
+
+    /// Split the expression into `a` and `b` such that it equals `a * symbol + b`, if it's at
+    /// most linear in `symbol`.
+    fn linear_in(&self, symbol: &str) -> Option<(Expr, Expr)> {
+        let (mut a, mut b) = (Expr::default(), Expr::default());
+
+        for (symbols, c) in &self.terms {
+            let mut rest = symbols.clone();
+            match rest.iter().filter(|s| *s == symbol).count() {
+                0 => b.add_term(rest, *c),
+                1 => {
+                    rest.retain(|s| s != symbol);
+                    a.add_term(rest, *c);
+                }
+                _ => return None,
+            }
+        }
+
+        Some((a, b))
+    }
+
+    /// Find values for the symbols, each within its range, that make the expression equal
+    /// `target`. Returns the values in the order of `ranges`, trying earlier symbols' values in
+    /// ascending order.
+    ///
+    /// Every symbol but the last is searched exhaustively; the last is solved for directly, which
+    /// needs the expression to be at most linear in it.
+    pub fn solve(&self, target: i64, ranges: &[(&str, RangeInclusive<i64>)]) -> Option<Vec<i64>> {
+        let ((last, last_range), others) = ranges.split_last()?;
+        let (a, b) = self.linear_in(last)?;
+        let mut values: Vec<i64> = others.iter().map(|(_, range)| *range.start()).collect();
+
+        loop {
+            let lookup = |symbol: &str| {
+                others
+                    .iter()
+                    .position(|(name, _)| *name == symbol)
+                    .map_or(0, |i| values[i])
+            };
+            let solution = match (a.eval(lookup), b.eval(lookup)) {
+                (Some(0), Some(b)) => (b == target).then(|| *last_range.start()),
+                (Some(a), Some(b)) => target.checked_sub(b).and_then(|difference| match difference
+                    .checked_rem(a)
+                {
+                    Some(0) => difference.checked_div(a),
+                    _ => None,
+                }),
+                // The program would overflow with these values.
+                _ => None,
+            };
+            if let Some(x) = solution.filter(|x| last_range.contains(x)) {
+                values.push(x);
+                return Some(values);
+            }
+
+            // Move on to the next combination of the other symbols' values, like an odometer.
+            let mut i = others.len();
+            loop {
+                if i == 0 {
+                    return None;
+                }
+                i -= 1;
+
+                if values[i] < *others[i].1.end() {
+                    values[i] += 1;
+                    break;
+                }
+                values[i] = *others[i].1.start();
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+
+        // Highest degree terms first, so the constant ends up last.
+        let mut terms: Vec<(&Vec<String>, &i64)> = self.terms.iter().collect();
+        terms.sort_by_key(|(symbols, _)| std::cmp::Reverse(symbols.len()));
+
+        for (i, (symbols, c)) in terms.into_iter().enumerate() {
+            let sign = if *c < 0 { "-" } else { "+" };
+            match i {
+                0 if *c < 0 => write!(f, "-")?,
+                0 => {}
+                _ => write!(f, " {sign} ")?,
+            }
+
+            let magnitude = c.unsigned_abs();
+            if symbols.is_empty() {
+                write!(f, "{magnitude}")?;
+            } else {
+                if magnitude != 1 {
+                    write!(f, "{magnitude}*")?;
+                }
+                write!(f, "{}", symbols.join("*"))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The contents of a memory cell during symbolic execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Known(Expr),
+    /// Read from an address that depends on a symbol, so it could be anything.
+    Unknown,
+}
+
+impl Value {
+    fn constant(value: i64) -> Self {
+        Value::Known(Expr::constant(value))
+    }
+
+    /// The value if it doesn't depend on any symbol.
+    pub fn as_constant(&self) -> Option<i64> {
+        match self {
+            Value::Known(expr) => expr.as_constant(),
+            Value::Unknown => None,
+        }
+    }
+
+    pub fn as_expr(&self) -> Option<&Expr> {
+        match self {
+            Value::Known(expr) => Some(expr),
+            Value::Unknown => None,
+        }
+    }
+
+    fn combine(&self, other: &Value, f: impl Fn(&Expr, &Expr) -> Expr) -> Value {
+        match (self, other) {
+            (Value::Known(a), Value::Known(b)) => Value::Known(f(a, b)),
+            _ => Value::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Known(expr) => write!(f, "{expr}"),
+            Value::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolicError {
+    /// The program failed as it would on a concrete machine.
+    Intcode(IntcodeError),
+    /// Something the instruction at `ip` uses to decide what to do, described by `what`, depends
+    /// on a symbol.
+    NotConcrete {
+        ip: usize,
+        what: &'static str,
+        value: Value,
+    },
+}
+
+impl From<IntcodeError> for SymbolicError {
+    fn from(error: IntcodeError) -> Self {
+        SymbolicError::Intcode(error)
+    }
+}
+
+impl fmt::Display for SymbolicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolicError::Intcode(error) => write!(f, "{error}"),
+            SymbolicError::NotConcrete { ip, what, value } => {
+                write!(f, "[{ip}] {what} isn't concrete: {value}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SymbolicError {}
+
+/// A machine whose memory cells hold expressions rather than numbers. Input is concrete.
+#[derive(Debug, Clone)]
+pub struct SymbolicComputer {
+    mem: HashMap<usize, Value>,
+    ip: usize,
+    relative_base: i64,
+    input: VecDeque<i64>,
+    output: Vec<Value>,
+}
+
+impl SymbolicComputer {
+    /// Load `program`, replacing the cell at each address in `symbols` with the named symbol.
+    pub fn new(program: &[i64], symbols: &[(usize, &str)]) -> Self {
+        let mut mem: HashMap<usize, Value> = program
+            .iter()
+            .enumerate()
+            .map(|(addr, value)| (addr, Value::constant(*value)))
+            .collect();
+        for (addr, name) in symbols {
+            mem.insert(*addr, Value::Known(Expr::symbol(name)));
+        }
+
+        SymbolicComputer {
+            mem,
+            ip: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+            output: vec![],
+        }
+    }
+
+    pub fn push_input(&mut self, value: i64) {
+        self.input.push_back(value);
+    }
+
+    pub fn output(&self) -> &[Value] {
+        &self.output
+    }
+
+    pub fn read(&self, addr: usize) -> Value {
+        self.mem
+            .get(&addr)
+            .cloned()
+            .unwrap_or_else(|| Value::constant(0))
+    }
+
+    /// The raw value of the current instruction, for errors.
+    fn instruction(&self) -> i64 {
+        self.read(self.ip).as_constant().unwrap_or(0)
+    }
+
+    /// The error for an instruction whose arithmetic overflows an `i64`.
+    fn overflow(&self) -> IntcodeError {
+        IntcodeError::Overflow {
+            ip: self.ip,
+            instruction: self.instruction(),
+        }
+    }
+
+    fn concrete(&self, value: Value, what: &'static str) -> Result<i64, SymbolicError> {
+        value.as_constant().ok_or(SymbolicError::NotConcrete {
+            ip: self.ip,
+            what,
+            value,
+        })
+    }
+
+    /// Resolve the address parameter `n` (1-based) of the current instruction refers to.
+    fn address(&self, ins: &Instruction, n: usize) -> Result<usize, SymbolicError> {
+        let param = self.concrete(self.read(self.ip + n), "address")?;
+        let address = match ins.mode(n) {
+            ParamMode::Position => param,
+            ParamMode::Relative => self
+                .relative_base
+                .checked_add(param)
+                .ok_or_else(|| self.overflow())?,
+            ParamMode::Immediate => {
+                return Err(IntcodeError::ImmediateWrite {
+                    ip: self.ip,
+                    instruction: self.instruction(),
+                    param: n as u32,
+                }
+                .into())
+            }
+        };
+
+        usize::try_from(address).map_err(|_| {
+            IntcodeError::NegativeAddress {
+                ip: self.ip,
+                instruction: self.instruction(),
+                address,
+            }
+            .into()
+        })
+    }
+
+    fn param(&self, ins: &Instruction, n: usize) -> Result<Value, SymbolicError> {
+        match ins.mode(n) {
+            ParamMode::Immediate => Ok(self.read(self.ip + n)),
+            // Reading through an address that depends on a symbol could give anything.
+            _ => match self.address(ins, n) {
+                Ok(addr) => Ok(self.read(addr)),
+                Err(SymbolicError::NotConcrete { .. }) => Ok(Value::Unknown),
+                Err(error) => Err(error),
+            },
+        }
+    }
+
+    /// Execute a single instruction, returning whether the program is still running.
+    pub fn step(&mut self) -> Result<bool, SymbolicError> {
+        let raw = self.concrete(self.read(self.ip), "instruction")?;
+        let ins = Instruction::decode(self.ip, raw)?;
+        let mut next = self.ip + ins.size();
+
+        match ins.opcode {
+            Opcode::Add | Opcode::Multiply => {
+                let (a, b) = (self.param(&ins, 1)?, self.param(&ins, 2)?);
+                let dest = self.address(&ins, 3)?;
+                let add = ins.opcode == Opcode::Add;
+                let result = match (a.as_constant(), b.as_constant()) {
+                    (Some(a), Some(b)) => {
+                        let result = if add {
+                            a.checked_add(b)
+                        } else {
+                            a.checked_mul(b)
+                        };
+                        Value::constant(result.ok_or_else(|| self.overflow())?)
+                    }
+                    _ if add => a.combine(&b, Expr::add),
+                    _ => a.combine(&b, Expr::mul),
+                };
+                self.mem.insert(dest, result);
+            }
+
+            Opcode::Input => {
+                let dest = self.address(&ins, 1)?;
+                let value = self.input.pop_front().ok_or(IntcodeError::InputExhausted {
+                    ip: self.ip,
+                    instruction: raw,
+                })?;
+                self.mem.insert(dest, Value::constant(value));
+            }
+
+            Opcode::Output => {
+                let value = self.param(&ins, 1)?;
+                self.output.push(value);
+            }
+
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => {
+                // Both parameters are read even when the jump isn't taken, as on a concrete machine.
+                let (condition, target) = (self.param(&ins, 1)?, self.param(&ins, 2)?);
+                let condition = self.concrete(condition, "jump condition")?;
+                if (condition != 0) == (ins.opcode == Opcode::JumpIfTrue) {
+                    let target = self.concrete(target, "jump target")?;
+                    next = usize::try_from(target).map_err(|_| IntcodeError::NegativeAddress {
+                        ip: self.ip,
+                        instruction: raw,
+                        address: target,
+                    })?;
+                }
+            }
+
+            Opcode::LessThan | Opcode::Equals => {
+                let a = self.concrete(self.param(&ins, 1)?, "comparison operand")?;
+                let b = self.concrete(self.param(&ins, 2)?, "comparison operand")?;
+                let result = if ins.opcode == Opcode::LessThan {
+                    a < b
+                } else {
+                    a == b
+                };
+                let dest = self.address(&ins, 3)?;
+                self.mem.insert(dest, Value::constant(i64::from(result)));
+            }
+
+            Opcode::AdjustRelativeBase => {
+                let offset = self.concrete(self.param(&ins, 1)?, "relative base offset")?;
+                self.relative_base = self
+                    .relative_base
+                    .checked_add(offset)
+                    .ok_or_else(|| self.overflow())?;
+            }
+
+            Opcode::Halt => return Ok(false),
+        }
+
+        self.ip = next;
+        Ok(true)
+    }
+
+    /// Run the program until it halts.
+    pub fn run(&mut self) -> Result<(), SymbolicError> {
+        while self.step()? {}
+        Ok(())
+    }
+}
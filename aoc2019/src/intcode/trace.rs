@@ -0,0 +1,147 @@
+//! Execution traces and instruction profiles for Intcode programs.
+
+use super::{Instruction, Opcode};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+
+/// A record of one executed instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// Where the instruction was.
+    pub ip: usize,
+    pub instruction: Instruction,
+    /// The values of the parameters the instruction read, in order, after resolving their modes.
+    /// Unused entries are 0; the destination of a write is in `written` instead.
+    pub operands: [i64; 3],
+    /// The address the instruction wrote to and the value it wrote.
+    pub written: Option<(usize, i64)>,
+}
+
+impl TraceEntry {
+    /// The operands the instruction read.
+    pub fn read_operands(&self) -> &[i64] {
+        let opcode = self.instruction.opcode;
+        let count = opcode.param_count() - usize::from(opcode.write_param().is_some());
+        &self.operands[..count]
+    }
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}: {}", self.ip, self.instruction.opcode.mnemonic())?;
+
+        let operands: Vec<String> = self.read_operands().iter().map(i64::to_string).collect();
+        if !operands.is_empty() {
+            write!(f, " {}", operands.join(", "))?;
+        }
+
+        if let Some((addr, value)) = self.written {
+            write!(f, " -> [{addr}] = {value}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Somewhere to send the instructions a machine executes.
+pub trait TraceSink {
+    fn record(&mut self, entry: &TraceEntry);
+}
+
+/// Discards everything; used when tracing is off.
+impl TraceSink for () {
+    #[inline(always)]
+    fn record(&mut self, _: &TraceEntry) {}
+}
+
+impl TraceSink for Vec<TraceEntry> {
+    fn record(&mut self, entry: &TraceEntry) {
+        self.push(*entry);
+    }
+}
+
+impl<F: FnMut(&TraceEntry)> TraceSink for F {
+    fn record(&mut self, entry: &TraceEntry) {
+        self(entry)
+    }
+}
+
+/// Records to both sinks, e.g. to profile and log at the same time.
+impl<A: TraceSink, B: TraceSink> TraceSink for (A, B) {
+    fn record(&mut self, entry: &TraceEntry) {
+        self.0.record(entry);
+        self.1.record(entry);
+    }
+}
+
+/// Writes each entry as a line of text.
+pub struct TraceWriter<W>(pub W);
+
+impl<W: Write> TraceSink for TraceWriter<W> {
+    fn record(&mut self, entry: &TraceEntry) {
+        // A trace is a debugging aid, so a failing writer shouldn't stop the program.
+        let _ = writeln!(self.0, "{entry}");
+    }
+}
+
+/// Counts of executed instructions, by opcode and by address.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    total: u64,
+    by_opcode: HashMap<Opcode, u64>,
+    by_address: HashMap<usize, u64>,
+}
+
+impl Profile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of instructions executed.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Instruction counts per opcode, most executed first.
+    pub fn by_opcode(&self) -> Vec<(Opcode, u64)> {
+        let mut counts: Vec<(Opcode, u64)> = self.by_opcode.iter().map(|(o, n)| (*o, *n)).collect();
+        counts.sort_by_key(|(opcode, n)| (std::cmp::Reverse(*n), opcode.code()));
+        counts
+    }
+
+    /// The `n` most executed instruction addresses with their counts. Loops the program spends
+    /// most of its time in show up here.
+    pub fn hot_spots(&self, n: usize) -> Vec<(usize, u64)> {
+        let mut counts: Vec<(usize, u64)> = self.by_address.iter().map(|(a, c)| (*a, *c)).collect();
+        counts.sort_by_key(|(addr, count)| (std::cmp::Reverse(*count), *addr));
+        counts.truncate(n);
+        counts
+    }
+}
+
+impl TraceSink for Profile {
+    fn record(&mut self, entry: &TraceEntry) {
+        self.total += 1;
+        *self.by_opcode.entry(entry.instruction.opcode).or_default() += 1;
+        *self.by_address.entry(entry.ip).or_default() += 1;
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} instructions executed", self.total)?;
+
+        writeln!(f, "by opcode:")?;
+        for (opcode, count) in self.by_opcode() {
+            writeln!(f, "  {:<4} {count}", opcode.mnemonic())?;
+        }
+
+        writeln!(f, "hottest addresses:")?;
+        for (addr, count) in self.hot_spots(10) {
+            writeln!(f, "  {addr:04} {count}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -11,5 +11,11 @@ pub mod day_05;
 pub mod day_07;
 pub mod day_08;
 pub mod day_09;
+pub mod day_11;
+pub mod day_13;
+pub mod day_15;
+pub mod day_23;
+pub mod day_25;
+pub mod intcode;
 
 aoc_lib! { year = 2019 }
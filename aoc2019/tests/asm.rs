@@ -0,0 +1,141 @@
+//! Tests for the Intcode assembler and disassembler: the documented example, round trips through a
+//! listing, and the line every kind of error is reported on.
+
+use aoc2019::intcode::asm::{assemble, AssembleError, AssembleErrorKind};
+use aoc2019::intcode::disasm::listing;
+use aoc2019::intcode::{parse_program, IntcodeComputer};
+use std::fs;
+
+fn input(day: u32) -> Vec<i64> {
+    let path = format!("input/2019/day{day}.txt");
+    parse_program(&fs::read_to_string(&path).unwrap_or_else(|e| panic!("reading {path}: {e}")))
+}
+
+/// Check that `source` fails to assemble with `kind` on `line`.
+fn assert_error(source: &str, line: usize, kind: AssembleErrorKind) {
+    assert_eq!(assemble(source), Err(AssembleError { line, kind }));
+}
+
+fn assert_round_trip(program: &[i64]) {
+    let source = listing(program);
+    let assembled = assemble(&source).unwrap_or_else(|e| panic!("{e}\n{source}"));
+    assert_eq!(assembled, program);
+}
+
+#[test]
+fn documented_example() {
+    let source = "
+        ; Output the numbers 5 down to 1.
+                add  #5, #0, [count]
+        loop:   out  [count]
+                add  [count], #-1, [count]
+                jnz  [count], #loop
+                hlt
+        count:  .data 0
+    ";
+
+    let program = assemble(source).unwrap();
+    let mut computer = IntcodeComputer::new(&program);
+    computer.run().unwrap();
+    assert_eq!(computer.output(), &[5, 4, 3, 2, 1]);
+}
+
+#[test]
+fn expressions_and_relative_operands() {
+    let source = "
+        arb #table
+        out rb+1
+        out rb
+        out rb-table+end
+        hlt
+        table: .data -3, 9223372036854775807
+        end:   .data -9223372036854775808, end-table
+    ";
+
+    let program = assemble(source).unwrap();
+    let mut computer = IntcodeComputer::new(&program);
+    computer.run().unwrap();
+    assert_eq!(computer.output(), &[i64::MAX, -3, i64::MIN]);
+}
+
+#[test]
+fn day_05_round_trip() {
+    assert_round_trip(&input(5));
+}
+
+#[test]
+fn day_09_round_trip() {
+    assert_round_trip(&input(9));
+}
+
+#[test]
+fn extreme_values_round_trip() {
+    assert_round_trip(&[104, i64::MIN, 104, i64::MAX, 1101, i64::MIN, -1, 0, 99]);
+}
+
+#[test]
+fn stray_mode_digits_round_trip() {
+    assert_round_trip(&[1104, 7, 99]);
+    assert_round_trip(&[199]);
+    assert_round_trip(&[104, 1, 21199, 3, 1105, 1, 0]);
+}
+
+#[test]
+fn unknown_mnemonic() {
+    let kind = AssembleErrorKind::UnknownMnemonic("jmp".into());
+    assert_error("hlt\njmp #0", 2, kind);
+}
+
+#[test]
+fn unknown_directive() {
+    let kind = AssembleErrorKind::UnknownDirective(".word".into());
+    assert_error("hlt\n\n.word 1", 3, kind);
+}
+
+#[test]
+fn wrong_operand_count() {
+    let kind = AssembleErrorKind::WrongOperandCount {
+        expected: 3,
+        found: 2,
+    };
+    assert_error("; comment\nadd #1, #2", 2, kind);
+}
+
+#[test]
+fn invalid_operand() {
+    let kind = AssembleErrorKind::InvalidOperand("5".into());
+    assert_error("out #5\nout 5", 2, kind);
+}
+
+#[test]
+fn invalid_label() {
+    let kind = AssembleErrorKind::InvalidLabel("1st".into());
+    assert_error("hlt\n1st: hlt", 2, kind);
+}
+
+#[test]
+fn duplicate_label() {
+    let kind = AssembleErrorKind::DuplicateLabel("start".into());
+    assert_error("start: hlt\nstart: hlt", 2, kind);
+}
+
+#[test]
+fn undefined_label() {
+    let kind = AssembleErrorKind::UndefinedLabel("nowhere".into());
+    assert_error("hlt\nhlt\njnz #1, #nowhere", 3, kind);
+}
+
+#[test]
+fn immediate_write() {
+    let kind = AssembleErrorKind::ImmediateWrite("#3".into());
+    assert_error("hlt\nadd #1, #2, #3", 2, kind);
+}
+
+#[test]
+fn overflow() {
+    let kind = AssembleErrorKind::Overflow("9223372036854775807+1".into());
+    assert_error("out #9223372036854775807+1\nhlt", 1, kind);
+
+    let kind = AssembleErrorKind::Overflow("-9223372036854775808-end".into());
+    assert_error("hlt\n.data -9223372036854775808-end\nend: hlt", 2, kind);
+}
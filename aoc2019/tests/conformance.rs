@@ -0,0 +1,290 @@
+//! Conformance tests for the shared Intcode machine: every example program from the puzzle text
+//! of days 2, 5, 7 and 9, and the real puzzle inputs with their known answers.
+//!
+//! Each program runs both with and without the decode cache, and the two runs must agree.
+
+use aoc2019::intcode::network::{Network, Topology};
+use aoc2019::intcode::{parse_program, Event, IntcodeComputer, IntcodeError, Snapshot};
+use itertools::Itertools;
+use std::fs;
+
+/// Run `program` to completion with `inputs` queued, returning the finished machine.
+fn run(program: &[i64], inputs: &[i64]) -> IntcodeComputer {
+    let machines: Vec<IntcodeComputer> = [false, true]
+        .into_iter()
+        .map(|decode_cache| {
+            let mut computer = IntcodeComputer::new(program);
+            computer.set_decode_cache(decode_cache);
+            for input in inputs {
+                computer.push_input(*input);
+            }
+            computer.run().expect("program failed");
+            computer
+        })
+        .collect();
+
+    let (plain, cached) = (&machines[0], &machines[1]);
+    assert_eq!(
+        plain.output(),
+        cached.output(),
+        "engines disagree on output"
+    );
+    for addr in 0..program.len() {
+        assert_eq!(
+            plain.read(addr),
+            cached.read(addr),
+            "engines disagree on [{addr}]"
+        );
+    }
+
+    machines.into_iter().next().unwrap()
+}
+
+/// Run `program` with `inputs`, returning what it output.
+fn outputs(program: &str, inputs: &[i64]) -> Vec<i64> {
+    run(&parse_program(program), inputs).into_io().1
+}
+
+/// Run an already parsed `program` with a single input, returning what it output.
+fn outputs_of(program: &[i64], input: i64) -> Vec<i64> {
+    run(program, &[input]).into_io().1
+}
+
+/// Run `program`, returning its memory over the program's original length.
+fn final_memory(program: &str) -> Vec<i64> {
+    let program = parse_program(program);
+    let computer = run(&program, &[]);
+    (0..program.len()).map(|addr| computer.read(addr)).collect()
+}
+
+fn input(day: u32) -> Vec<i64> {
+    let path = format!("input/2019/day{day}.txt");
+    parse_program(&fs::read_to_string(&path).unwrap_or_else(|e| panic!("reading {path}: {e}")))
+}
+
+#[test]
+fn day_02_examples() {
+    let examples = [
+        (
+            "1,9,10,3,2,3,11,0,99,30,40,50",
+            "3500,9,10,70,2,3,11,0,99,30,40,50",
+        ),
+        ("1,0,0,0,99", "2,0,0,0,99"),
+        ("2,3,0,3,99", "2,3,0,6,99"),
+        ("2,4,4,5,99,0", "2,4,4,5,99,9801"),
+        ("1,1,1,4,99,5,6,0,99", "30,1,1,4,2,5,6,0,99"),
+    ];
+
+    for (program, expected) in examples {
+        assert_eq!(final_memory(program), parse_program(expected), "{program}");
+    }
+}
+
+#[test]
+fn day_05_io_and_modes() {
+    assert_eq!(outputs("3,0,4,0,99", &[42]), [42]);
+    assert_eq!(final_memory("1002,4,3,4,33"), [1002, 4, 3, 4, 99]);
+    assert_eq!(final_memory("1101,100,-1,4,0"), [1101, 100, -1, 4, 99]);
+}
+
+#[test]
+fn day_05_comparisons() {
+    let equal_to_8 = ["3,9,8,9,10,9,4,9,99,-1,8", "3,3,1108,-1,8,3,4,3,99"];
+    let less_than_8 = ["3,9,7,9,10,9,4,9,99,-1,8", "3,3,1107,-1,8,3,4,3,99"];
+
+    for input in [-3, 0, 7, 8, 9, 100] {
+        for program in equal_to_8 {
+            assert_eq!(
+                outputs(program, &[input]),
+                [i64::from(input == 8)],
+                "{program}"
+            );
+        }
+        for program in less_than_8 {
+            assert_eq!(
+                outputs(program, &[input]),
+                [i64::from(input < 8)],
+                "{program}"
+            );
+        }
+    }
+}
+
+#[test]
+fn day_05_jumps() {
+    let programs = [
+        "3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9",
+        "3,3,1105,-1,9,1101,0,0,12,4,12,99,1",
+    ];
+
+    for program in programs {
+        assert_eq!(outputs(program, &[0]), [0], "{program}");
+        assert_eq!(outputs(program, &[5]), [1], "{program}");
+        assert_eq!(outputs(program, &[-5]), [1], "{program}");
+    }
+}
+
+#[test]
+fn day_05_compare_to_8() {
+    let program = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,\
+                   125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+
+    assert_eq!(outputs(program, &[7]), [999]);
+    assert_eq!(outputs(program, &[8]), [1000]);
+    assert_eq!(outputs(program, &[9]), [1001]);
+}
+
+/// The highest signal any ordering of `phases` gets out of a network of five amplifiers.
+fn max_thruster_signal(program: &[i64], phases: [i64; 5], topology: Topology) -> i64 {
+    phases
+        .into_iter()
+        .permutations(5)
+        .map(|phases| {
+            let mut amplifiers = Network::new(program, 5, topology);
+            for (n, phase) in phases.into_iter().enumerate() {
+                amplifiers.push_input(n, phase);
+            }
+            amplifiers.push_input(0, 0);
+            amplifiers.run().expect("amplifiers failed");
+            *amplifiers.output().last().unwrap()
+        })
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn day_07_examples() {
+    let chain = parse_program("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0");
+    assert_eq!(
+        max_thruster_signal(&chain, [0, 1, 2, 3, 4], Topology::Chain),
+        43210
+    );
+
+    let ring = parse_program(
+        "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+    );
+    assert_eq!(
+        max_thruster_signal(&ring, [5, 6, 7, 8, 9], Topology::Ring),
+        139629729
+    );
+}
+
+#[test]
+fn day_09_quine() {
+    let program = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+    assert_eq!(outputs(program, &[]), parse_program(program));
+}
+
+#[test]
+fn day_09_large_numbers() {
+    let output = outputs("1102,34915192,34915192,7,4,7,99,0", &[]);
+    assert_eq!(output, [34915192 * 34915192]);
+    assert_eq!(output[0].to_string().len(), 16);
+
+    assert_eq!(outputs("104,1125899906842624,99", &[]), [1125899906842624]);
+}
+
+#[test]
+fn overflow_is_an_error() {
+    // Each program with the address of the instruction that overflows.
+    let programs: [(&[i64], usize); 4] = [
+        (&[1101, i64::MAX, 1, 0, 99], 0),
+        (&[1102, 1 << 62, 4, 0, 99], 0),
+        (&[109, i64::MAX, 109, 1, 99], 2),
+        (&[109, i64::MAX, 22201, 1, 1, 0, 99], 2),
+    ];
+
+    for (program, ip) in programs {
+        for decode_cache in [false, true] {
+            let mut computer = IntcodeComputer::new(program);
+            computer.set_decode_cache(decode_cache);
+            let instruction = program[ip];
+            assert_eq!(
+                computer.run(),
+                Err(IntcodeError::Overflow { ip, instruction }),
+                "{program:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn code_at_a_sparse_address() {
+    // Write a halt a trillion cells out, then jump to it.
+    let program = [1101, 99, 0, 1_000_000_000_000, 1105, 1, 1_000_000_000_000];
+    let computer = run(&program, &[]);
+    assert_eq!(computer.ip(), 1_000_000_000_000);
+    assert_eq!(computer.read(1_000_000_000_000), 99);
+}
+
+#[test]
+fn write_to_the_highest_address() {
+    for decode_cache in [false, true] {
+        let mut computer = IntcodeComputer::new(&[99]);
+        computer.set_decode_cache(decode_cache);
+        computer.write(usize::MAX, 7);
+        assert_eq!(computer.read(usize::MAX), 7);
+    }
+}
+
+#[test]
+fn snapshot_addresses_must_fit_in_i64() {
+    for text in [
+        "ip -1\nmem 99\n",
+        "ip 18446744073709551615\ncell 18446744073709551615 99\n",
+        "ip 0\ncell 9223372036854775808 99\n",
+    ] {
+        let loaded: Result<Snapshot, _> = Snapshot::read_from(text.as_bytes());
+        assert!(loaded.is_err(), "{text}");
+    }
+
+    let text = "ip 9223372036854775807\ncell 9223372036854775807 99\n";
+    let mut computer: IntcodeComputer = Snapshot::read_from(text.as_bytes()).unwrap().into();
+    assert_eq!(computer.step(), Ok(Some(Event::Halted)));
+}
+
+#[test]
+fn day_02_input() {
+    let program = input(2);
+    let run_with = |noun, verb| {
+        let mut patched = program.clone();
+        patched[1] = noun;
+        patched[2] = verb;
+        run(&patched, &[]).read(0)
+    };
+
+    assert_eq!(run_with(12, 2), 5482655);
+    assert_eq!(run_with(49, 67), 19690720);
+}
+
+#[test]
+fn day_05_input() {
+    let program = input(5);
+
+    let output = outputs_of(&program, 1);
+    let (diagnostic, tests) = output.split_last().expect("no output");
+    assert!(tests.iter().all(|t| *t == 0), "failed tests: {tests:?}");
+    assert_eq!(*diagnostic, 2845163);
+
+    assert_eq!(outputs_of(&program, 5), [9436229]);
+}
+
+#[test]
+fn day_07_input() {
+    let program = input(7);
+    assert_eq!(
+        max_thruster_signal(&program, [0, 1, 2, 3, 4], Topology::Chain),
+        70597
+    );
+    assert_eq!(
+        max_thruster_signal(&program, [5, 6, 7, 8, 9], Topology::Ring),
+        30872528
+    );
+}
+
+#[test]
+fn day_09_input() {
+    let program = input(9);
+    assert_eq!(outputs_of(&program, 1), [2932210790]);
+    assert_eq!(outputs_of(&program, 2), [73144]);
+}
@@ -0,0 +1,223 @@
+//! Property tests that run randomly generated Intcode programs and check the interpreter's
+//! invariants. Every run has an instruction budget, so programs that loop forever just fail.
+
+use aoc2019::intcode::symbolic::{SymbolicComputer, SymbolicError, Value};
+use aoc2019::intcode::{Event, IntcodeComputer, IntcodeError, Memory, Snapshot};
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+const BUDGET: u64 = 2_000;
+
+/// A raw instruction with a valid or nearly valid opcode and random modes, some of them invalid.
+fn instruction() -> impl Strategy<Value = i64> {
+    let opcodes = prop::sample::select(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 99, 0, 42]);
+    (opcodes, 0..4i64, 0..4i64, 0..3i64)
+        .prop_map(|(opcode, a, b, c)| opcode + 100 * a + 1000 * b + 10000 * c)
+}
+
+/// Cells that are mostly instructions and small addresses, with the odd huge value.
+fn cell() -> impl Strategy<Value = i64> {
+    prop_oneof![
+        4 => instruction(),
+        4 => -5..80i64,
+        2 => any::<i64>(),
+        1 => prop::sample::select(vec![i64::MIN, i64::MAX]),
+    ]
+}
+
+/// A program that writes a few random cells far past its own end, then jumps to them, so the
+/// code it goes on to run lives just past the dense region of memory or in the sparse one.
+fn far_program() -> impl Strategy<Value = Vec<i64>> {
+    let far = prop_oneof![80..0x2000i64, 1i64 << 20..1i64 << 40];
+    let code = prop::collection::vec(cell(), 1..8);
+    let rest = prop::collection::vec(cell(), 0..40);
+
+    (far, code, rest).prop_map(|(far, code, rest)| {
+        let mut program = vec![];
+        for (offset, value) in (0..).zip(code) {
+            program.extend([1101, value, 0, far + offset]);
+        }
+        program.extend([1105, 1, far]);
+        program.extend(rest);
+        program
+    })
+}
+
+/// A program that starts by adding and multiplying huge immediates, so its arithmetic overflows.
+fn overflow_program() -> impl Strategy<Value = Vec<i64>> {
+    let huge = || prop_oneof![any::<i64>(), Just(i64::MIN), Just(i64::MAX), Just(1 << 62)];
+    let op = (
+        prop::sample::select(vec![1101, 1102]),
+        huge(),
+        huge(),
+        0..80i64,
+    );
+    let ops = prop::collection::vec(op, 1..6);
+    let rest = prop::collection::vec(cell(), 0..40);
+
+    (ops, rest).prop_map(|(ops, rest)| {
+        let mut program = vec![];
+        for (opcode, a, b, dest) in ops {
+            program.extend([opcode, a, b, dest]);
+        }
+        program.extend(rest);
+        program
+    })
+}
+
+fn program() -> impl Strategy<Value = Vec<i64>> {
+    prop_oneof![
+        3 => prop::collection::vec(cell(), 1..80),
+        1 => far_program(),
+        1 => overflow_program(),
+    ]
+}
+
+fn inputs() -> impl Strategy<Value = Vec<i64>> {
+    prop::collection::vec(-10..10i64, 0..6)
+}
+
+/// Everything observable about a machine after a run.
+#[derive(Debug, PartialEq, Eq)]
+struct Outcome {
+    result: Result<(), IntcodeError>,
+    ip: usize,
+    relative_base: i64,
+    output: Vec<i64>,
+    memory: BTreeMap<usize, i64>,
+}
+
+/// Every nonzero cell, whether it's stored densely or sparsely.
+fn nonzero_cells(memory: &Memory) -> BTreeMap<usize, i64> {
+    (0..)
+        .zip(memory.dense().iter().copied())
+        .chain(memory.sparse())
+        .filter(|(_, value)| *value != 0)
+        .collect()
+}
+
+impl Outcome {
+    fn of(computer: &IntcodeComputer, result: Result<(), IntcodeError>) -> Self {
+        Outcome {
+            result,
+            ip: computer.ip(),
+            relative_base: computer.relative_base(),
+            output: computer.output().clone(),
+            memory: nonzero_cells(computer.memory()),
+        }
+    }
+}
+
+fn machine(program: &[i64], inputs: &[i64]) -> IntcodeComputer {
+    let mut computer = IntcodeComputer::new(program);
+    for input in inputs {
+        computer.push_input(*input);
+    }
+    computer.set_budget(Some(BUDGET));
+    computer
+}
+
+fn run(mut computer: IntcodeComputer) -> Outcome {
+    let result = computer.run();
+    Outcome::of(&computer, result)
+}
+
+proptest! {
+    #[test]
+    fn never_panics(program in program(), inputs in inputs()) {
+        let _ = machine(&program, &inputs).run();
+    }
+
+    #[test]
+    fn stepping_matches_running(program in program(), inputs in inputs()) {
+        let mut stepped = machine(&program, &inputs);
+        let result = loop {
+            match stepped.step() {
+                Ok(Some(Event::Halted)) => break Ok(()),
+                Ok(Some(Event::NeedsInput)) => {
+                    break Err(IntcodeError::InputExhausted {
+                        ip: stepped.ip(),
+                        instruction: stepped.read(stepped.ip()),
+                    })
+                }
+                Ok(_) => {}
+                Err(error) => break Err(error),
+            }
+        };
+
+        prop_assert_eq!(Outcome::of(&stepped, result), run(machine(&program, &inputs)));
+    }
+
+    #[test]
+    fn snapshots_continue_identically(
+        program in program(),
+        inputs in inputs(),
+        steps in 0..50usize,
+    ) {
+        let mut computer = machine(&program, &inputs);
+        for _ in 0..steps {
+            if !matches!(computer.step(), Ok(None | Some(Event::Output(_)))) {
+                break;
+            }
+        }
+        let snapshot = computer.snapshot();
+
+        let mut text = vec![];
+        snapshot.write_to(&mut text).unwrap();
+        let mut loaded: IntcodeComputer = Snapshot::read_from(&text[..]).unwrap().into();
+        loaded.set_budget(Some(BUDGET));
+
+        computer.set_budget(Some(BUDGET));
+        let first = run(computer.clone());
+        computer.restore(&snapshot);
+        let second = run(computer);
+
+        prop_assert_eq!(&first, &second);
+        prop_assert_eq!(&first, &run(loaded));
+    }
+
+    #[test]
+    fn decode_cache_matches_interpreter(program in program(), inputs in inputs()) {
+        let mut cached = machine(&program, &inputs);
+        cached.set_decode_cache(true);
+
+        prop_assert_eq!(run(cached), run(machine(&program, &inputs)));
+    }
+
+    #[test]
+    fn symbolic_matches_interpreter(program in program(), inputs in inputs()) {
+        let concrete = run(machine(&program, &inputs));
+
+        let mut symbolic = SymbolicComputer::new(&program, &[]);
+        for input in &inputs {
+            symbolic.push_input(*input);
+        }
+        // The symbolic machine has no budget, so stop it after as many steps as the concrete one
+        // may take.
+        let mut result = Ok(true);
+        for _ in 0..=BUDGET {
+            result = symbolic.step();
+            if result != Ok(true) {
+                break;
+            }
+        }
+
+        match concrete.result {
+            Err(IntcodeError::BudgetExhausted { .. }) => {}
+            Err(error) => prop_assert_eq!(result, Err(SymbolicError::Intcode(error))),
+            Ok(()) => {
+                prop_assert_eq!(result, Ok(false));
+
+                let output: Vec<Option<i64>> =
+                    symbolic.output().iter().map(Value::as_constant).collect();
+                let expected: Vec<Option<i64>> =
+                    concrete.output.iter().copied().map(Some).collect();
+                prop_assert_eq!(output, expected);
+
+                for (addr, value) in concrete.memory {
+                    prop_assert_eq!(symbolic.read(addr).as_constant(), Some(value));
+                }
+            }
+        }
+    }
+}